@@ -1,77 +1,109 @@
 use std::ffi::{c_char, c_double, c_int, c_uint, c_void, CStr, CString};
+use std::panic::{catch_unwind, AssertUnwindSafe};
 use std::ptr::null_mut;
+use std::slice;
 use std::sync::Arc;
 
 use libc::{gid_t, uid_t};
 use map_in_place::MapVecInPlace;
-use tracing::debug;
+use tracing::{debug, error};
 
-use crate::callback::{SapiCallback, GLOBAL_CALLBACK};
+use crate::callback::{global_callback, SapiCallback};
+use crate::diagnostic::Diagnostic;
+use crate::error::record_last_error;
+use crate::request::RequestInfo;
 use crate::result::Ok;
-use crate::sys::sapi::{SapiHeaderOpEnum, SapiHeaderStruct, SapiHeadersStruct, SapiModuleStruct};
-use crate::sys::zend::{HashTable, ZendResult, ZendStat, Zval};
+use crate::sys::sapi::{
+    SapiHeaderOpEnum, SapiHeaderStruct, SapiHeadersStruct, SapiModuleStruct,
+    SAPI_HEADER_SEND_FAILED,
+};
+use crate::sys::zend::{HashTable, ZendResult, ZendStat, ZendString, Zval};
+use crate::zend::string::ZStr;
 
+/// `FAILURE`, returned in place of callbacks that panicked.
+const FAILURE: c_int = ZendResult::Failure as c_int;
+
+/// Runs the callback `name`, logging a panic in it and returning `failure`
+/// instead, so it does not unwind into the engine.
+fn guard<F, R>(name: &str, failure: R, f: F) -> R
+where
+    F: FnOnce() -> R,
+{
+    debug!("CALLBACK: {}", name);
+    match catch_unwind(AssertUnwindSafe(f)) {
+        std::result::Result::Ok(ret) => ret,
+        std::result::Result::Err(payload) => {
+            let message = payload
+                .downcast_ref::<&str>()
+                .copied()
+                .or_else(|| payload.downcast_ref::<String>().map(String::as_str))
+                .unwrap_or("Box<dyn Any>");
+            error!("SAPI callback {} panicked: {}", name, message);
+            failure
+        }
+    }
+}
+
+/// The callback of the running request if it has its own, or else of the
+/// SAPI.
 fn callback() -> Arc<dyn SapiCallback> {
-    unsafe {
-        Arc::clone(
-            &GLOBAL_CALLBACK
-                .as_ref()
-                .expect("SAPI callback must be registered before starting up")
-                .listener,
-        )
+    match unsafe { RequestInfo::current_callback() } {
+        Some(callback) => Arc::clone(&callback.listener),
+        _ => global_callback().expect("SAPI callback must be registered before starting up"),
     }
 }
 
 pub(crate) extern "C" fn on_startup(_sapi_module: *mut SapiModuleStruct) -> c_int {
-    debug!("CALLBACK: on_startup");
-    callback().on_startup().into()
+    guard("on_startup", FAILURE, || callback().on_startup().into())
 }
 
 pub(crate) extern "C" fn on_shutdown(_sapi_module: *mut SapiModuleStruct) -> c_int {
-    debug!("CALLBACK: on_shutdown");
-    callback().on_shutdown().into()
+    guard("on_shutdown", FAILURE, || callback().on_shutdown().into())
 }
 
 pub(crate) extern "C" fn on_activate() -> c_int {
-    debug!("CALLBACK: on_activate");
-    callback().on_activate().into()
+    guard("on_activate", FAILURE, || callback().on_activate().into())
 }
 
 pub(crate) extern "C" fn on_deactivate() -> c_int {
-    debug!("CALLBACK: on_deactivate");
-    callback().on_deactivate().into()
+    guard("on_deactivate", FAILURE, || {
+        callback().on_deactivate().into()
+    })
 }
 
 pub(crate) extern "C" fn on_ub_write(str: *const c_char, str_length: usize) -> usize {
-    debug!("CALLBACK: on_ub_write");
-    callback().on_ub_write(&unsafe { CStr::from_ptr(str) }.to_bytes()[..str_length])
+    guard("on_ub_write", 0, || {
+        callback().on_ub_write(unsafe { slice::from_raw_parts(str as *const u8, str_length) })
+    })
 }
 
 pub(crate) extern "C" fn on_flush(_server_context: *mut c_void) {
-    debug!("CALLBACK: on_flush");
-    callback().on_flush();
+    guard("on_flush", (), || callback().on_flush());
 }
 
 pub(crate) extern "C" fn on_get_stat() -> *mut ZendStat {
-    debug!("CALLBACK: on_get_stat");
-    match callback().on_get_stat() {
-        Ok(v) => Box::leak(Box::new(v)),
-        _ => null_mut(),
-    }
+    guard("on_get_stat", null_mut(), || {
+        match callback().on_get_stat() {
+            Ok(v) => Box::leak(Box::new(v)),
+            _ => null_mut(),
+        }
+    })
 }
 
 pub(crate) extern "C" fn on_getenv(name: *const c_char, name_len: usize) -> *mut c_char {
-    debug!("CALLBACK: on_getenv");
-    match callback().on_get_env(&unsafe { CStr::from_ptr(name) }.to_bytes()[..name_len]) {
-        Some(v) => unsafe { CString::from_vec_unchecked(v) }.into_raw(),
-        _ => null_mut(),
-    }
+    guard("on_getenv", null_mut(), || {
+        match callback().on_get_env(&unsafe { CStr::from_ptr(name) }.to_bytes()[..name_len]) {
+            Some(v) => unsafe { CString::from_vec_unchecked(v) }.into_raw(),
+            _ => null_mut(),
+        }
+    })
 }
 
 #[allow(clippy::unnecessary_cast)]
 pub(crate) unsafe extern "C" fn on_sapi_error(ty: c_int, error_msg: *const c_char, mut _args: ...) {
-    debug!("CALLBACK: on_sapi_error");
-    callback().on_sapi_error(ty as i32, unsafe { CStr::from_ptr(error_msg) }.to_bytes());
+    guard("on_sapi_error", (), || {
+        callback().on_sapi_error(ty as i32, unsafe { CStr::from_ptr(error_msg) }.to_bytes())
+    });
 }
 
 pub(crate) extern "C" fn on_header_handler(
@@ -79,94 +111,128 @@ pub(crate) extern "C" fn on_header_handler(
     op: SapiHeaderOpEnum,
     sapi_headers: *mut SapiHeadersStruct,
 ) -> c_int {
-    debug!("CALLBACK: on_header_handler");
-    callback()
-        .on_header_handler(unsafe { &*sapi_header }, op, unsafe { &mut *sapi_headers })
-        .into()
+    guard("on_header_handler", FAILURE, || {
+        callback()
+            .on_header_handler(unsafe { &*sapi_header }, op, unsafe { &mut *sapi_headers })
+            .into()
+    })
 }
 
 pub(crate) extern "C" fn on_send_headers(sapi_headers: *mut SapiHeadersStruct) -> c_int {
-    debug!("CALLBACK: on_send_headers");
-    callback().on_send_headers(unsafe { &*sapi_headers })
+    guard("on_send_headers", SAPI_HEADER_SEND_FAILED, || {
+        callback().on_send_headers(unsafe { &*sapi_headers })
+    })
 }
 
 pub(crate) extern "C" fn on_send_header(
     sapi_header: *mut SapiHeaderStruct,
     _server_context: *mut c_void,
 ) {
-    debug!("CALLBACK: on_send_header");
-    callback().on_send_header(unsafe { &*sapi_header });
+    guard("on_send_header", (), || {
+        callback().on_send_header(unsafe { &*sapi_header })
+    });
 }
 
 pub(crate) extern "C" fn on_read_post(buffer: *mut c_char, count_bytes: usize) -> usize {
-    debug!("CALLBACK: on_read_post");
-    let mut buffer = unsafe { CString::from_raw(buffer) }.into_bytes();
-    callback().on_read_post(&mut buffer[..count_bytes])
+    guard("on_read_post", 0, || {
+        callback()
+            .on_read_post(unsafe { slice::from_raw_parts_mut(buffer as *mut u8, count_bytes) })
+    })
 }
 
 pub(crate) extern "C" fn on_read_cookies() -> *mut c_char {
-    debug!("CALLBACK: on_read_cookies");
-    match callback().on_read_cookies() {
-        Some(v) => unsafe { CString::from_vec_unchecked(v) }.into_raw(),
-        _ => null_mut(),
-    }
+    guard("on_read_cookies", null_mut(), || {
+        match callback().on_read_cookies() {
+            Some(v) => unsafe { CString::from_vec_unchecked(v) }.into_raw(),
+            _ => null_mut(),
+        }
+    })
 }
 
 pub(crate) extern "C" fn on_register_server_variables(track_vars_array: *mut Zval) {
-    debug!("CALLBACK: on_register_server_variables");
-    callback().on_register_server_variables(unsafe { &mut *track_vars_array });
+    guard("on_register_server_variables", (), || {
+        callback().on_register_server_variables(unsafe { &mut *track_vars_array })
+    });
 }
 
 #[allow(clippy::unnecessary_cast)]
 pub(crate) extern "C" fn on_log_message(message: *const c_char, syslog_type_int: c_int) {
-    debug!("CALLBACK: on_log_message");
-    callback().on_log_message(
-        unsafe { CStr::from_ptr(message) }.to_bytes(),
-        syslog_type_int,
-    )
+    guard("on_log_message", (), || {
+        let message = unsafe { CStr::from_ptr(message) }.to_bytes();
+        record_last_error(message);
+        callback().on_log_message(message, syslog_type_int)
+    });
+}
+
+/// The error observer, registered while the modules start up.
+pub(crate) unsafe extern "C" fn on_error(
+    ty: c_int,
+    error_filename: *mut ZendString,
+    error_lineno: u32,
+    message: *mut ZendString,
+) {
+    // Messages often quote user data, which need not be valid UTF-8.
+    let message = ZStr::from(&*message);
+    record_last_error(message.as_bytes());
+    let message = String::from_utf8_lossy(message.as_bytes()).into_owned();
+    let file = error_filename
+        .as_ref()
+        .map(|file| String::from_utf8_lossy(ZStr::from(file).as_bytes()).into_owned());
+
+    guard("on_diagnostic", (), || {
+        callback().on_diagnostic(&Diagnostic::new(ty, message, file, error_lineno))
+    });
 }
 
 pub(crate) extern "C" fn on_get_request_time(request_time: *mut c_double) -> ZendResult {
-    debug!("CALLBACK: on_get_request_time");
-    callback()
-        .on_get_request_time()
-        .writing_raw(request_time)
-        .into()
+    guard("on_get_request_time", ZendResult::Failure, || {
+        callback()
+            .on_get_request_time()
+            .writing_raw(request_time)
+            .into()
+    })
 }
 
 pub(crate) extern "C" fn on_terminate_process() {
-    debug!("CALLBACK: on_terminate_process");
-    callback().on_get_request_time();
+    guard("on_terminate_process", (), || {
+        callback().on_get_request_time();
+    });
 }
 
 pub(crate) extern "C" fn on_default_post_reader() {
-    debug!("CALLBACK: on_default_post_reader");
-    callback().on_default_post_reader();
+    guard("on_default_post_reader", (), || {
+        callback().on_default_post_reader()
+    });
 }
 
 pub(crate) extern "C" fn on_treat_data(arg: c_int, str: *mut c_char, dest_array: *mut Zval) {
-    debug!("CALLBACK: on_treat_data");
-    callback().on_treat_data(arg, unsafe { CStr::from_ptr(str) }.to_bytes(), dest_array);
+    guard("on_treat_data", (), || {
+        callback().on_treat_data(arg, unsafe { CStr::from_ptr(str) }.to_bytes(), dest_array)
+    });
 }
 
 pub(crate) extern "C" fn on_get_fd(fd: *mut c_int) -> c_int {
-    debug!("CALLBACK: on_get_fd");
-    callback().on_get_fd().writing_raw(fd).into()
+    guard("on_get_fd", FAILURE, || {
+        callback().on_get_fd().writing_raw(fd).into()
+    })
 }
 
 pub(crate) extern "C" fn on_force_http_10() -> c_int {
-    debug!("CALLBACK: on_force_http_10");
-    callback().on_force_http_10().into()
+    guard("on_force_http_10", FAILURE, || {
+        callback().on_force_http_10().into()
+    })
 }
 
 pub(crate) extern "C" fn on_get_target_uid(uid: *mut uid_t) -> c_int {
-    debug!("CALLBACK: on_get_target_uid");
-    callback().on_get_target_uid().writing_raw(uid).into()
+    guard("on_get_target_uid", FAILURE, || {
+        callback().on_get_target_uid().writing_raw(uid).into()
+    })
 }
 
 pub(crate) extern "C" fn on_get_target_gid(gid: *mut gid_t) -> c_int {
-    debug!("CALLBACK: on_get_target_gid");
-    callback().on_get_target_gid().writing_raw(gid).into()
+    guard("on_get_target_gid", FAILURE, || {
+        callback().on_get_target_gid().writing_raw(gid).into()
+    })
 }
 
 #[allow(clippy::unnecessary_cast)]
@@ -177,25 +243,28 @@ pub(crate) extern "C" fn on_input_filter(
     val_len: usize,
     new_val_len: *mut usize,
 ) -> c_uint {
-    debug!("CALLBACK: on_input_filter");
-    callback()
-        .on_input_filter(
-            arg as i32,
-            unsafe { CStr::from_ptr(var) }.to_bytes(),
-            unsafe { Vec::from_raw_parts(val, val_len, val_len) }
-                .map_in_place(|p| unsafe { CString::from_raw(p) }.into_bytes().leak())
-                .leak(),
-        )
-        .writing_raw(new_val_len)
-        .into()
+    guard("on_input_filter", 0, || {
+        callback()
+            .on_input_filter(
+                arg as i32,
+                unsafe { CStr::from_ptr(var) }.to_bytes(),
+                unsafe { Vec::from_raw_parts(val, val_len, val_len) }
+                    .map_in_place(|p| unsafe { CString::from_raw(p) }.into_bytes().leak())
+                    .leak(),
+            )
+            .writing_raw(new_val_len)
+            .into()
+    })
 }
 
 pub(crate) extern "C" fn on_ini_defaults(configuration_hash: *mut HashTable) {
-    debug!("CALLBACK: on_ini_defaults");
-    callback().on_ini_defaults(unsafe { &mut *configuration_hash });
+    guard("on_ini_defaults", (), || {
+        callback().on_ini_defaults(unsafe { &mut *configuration_hash })
+    });
 }
 
 pub(crate) extern "C" fn on_input_filter_init() -> c_uint {
-    debug!("CALLBACK: on_input_filter_init");
-    callback().on_input_filter_init().into()
+    guard("on_input_filter_init", 0, || {
+        callback().on_input_filter_init().into()
+    })
 }
@@ -1,12 +1,15 @@
 use std::ffi::c_int;
+use std::fmt::{Debug, Formatter};
 use std::io::{stdout, Write};
 use std::process::exit;
-use std::sync::Arc;
+use std::sync::{Arc, PoisonError, RwLock};
 
 use libc::{gid_t, uid_t};
-use tracing::{debug, error, warn};
+use tracing::{debug, error, info, warn};
 
-use crate::result::{Ok, Result};
+use crate::diagnostic::{Diagnostic, Severity};
+use crate::error::Error;
+use crate::result::{Err, Ok, Result};
 use crate::sys::sapi::{
     SapiHeaderOpEnum, SapiHeaderStruct, SapiHeadersStruct, SAPI_HEADER_SENT_SUCCESSFULLY,
 };
@@ -28,8 +31,9 @@ macro_rules! default_behaviour {
     };
 }
 
+/// Called from every thread running PHP.
 #[allow(unused_variables)]
-pub trait SapiCallback {
+pub trait SapiCallback: Send + Sync {
     fn on_startup(&self) -> Result<()> {
         no_op!();
         Ok(())
@@ -69,7 +73,7 @@ pub trait SapiCallback {
 
     fn on_get_stat(&self) -> Result<ZendStat> {
         no_op!();
-        todo!()
+        Err(Error::custom("No stat of the request script is available"))
     }
 
     fn on_get_env(&self, name: &[u8]) -> Option<Vec<u8>> {
@@ -126,6 +130,17 @@ pub trait SapiCallback {
         )
     }
 
+    /// Called for every warning, notice, deprecation and error PHP raises,
+    /// whether or not it is displayed or logged.
+    fn on_diagnostic(&self, diagnostic: &Diagnostic) {
+        match diagnostic.severity() {
+            Severity::Error => error!("{}", diagnostic),
+            Severity::Warning => warn!("{}", diagnostic),
+            Severity::Notice => info!("{}", diagnostic),
+            Severity::Deprecated => debug!("{}", diagnostic),
+        }
+    }
+
     fn on_get_request_time(&self) -> Result<f64> {
         no_op!();
         Ok(0_f64)
@@ -179,6 +194,9 @@ pub trait SapiCallback {
     }
 }
 
+/// The callback of the SAPI, or of a single request when set on its
+/// [`RequestInfo`](crate::request::RequestInfo).
+#[derive(Clone)]
 pub struct Callback {
     listener: Arc<dyn SapiCallback>,
 }
@@ -194,10 +212,33 @@ impl Callback {
     }
 }
 
-static mut GLOBAL_CALLBACK: Option<Callback> = None;
+impl Debug for Callback {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Callback").finish_non_exhaustive()
+    }
+}
+
+static GLOBAL_CALLBACK: RwLock<Option<Callback>> = RwLock::new(None);
 
 pub(crate) fn register_global_callback(callback: Callback) {
-    unsafe {
-        GLOBAL_CALLBACK = Some(callback);
-    }
+    *GLOBAL_CALLBACK
+        .write()
+        .unwrap_or_else(PoisonError::into_inner) = Some(callback);
+}
+
+/// Releases the callback of the SAPI, once PHP has shut down.
+pub(crate) fn unregister_global_callback() {
+    GLOBAL_CALLBACK
+        .write()
+        .unwrap_or_else(PoisonError::into_inner)
+        .take();
+}
+
+/// The callback of the SAPI, while PHP is running.
+fn global_callback() -> Option<Arc<dyn SapiCallback>> {
+    GLOBAL_CALLBACK
+        .read()
+        .unwrap_or_else(PoisonError::into_inner)
+        .as_ref()
+        .map(|callback| Arc::clone(&callback.listener))
 }
@@ -0,0 +1,96 @@
+//! Warnings, notices, deprecations and errors reported by PHP.
+
+use std::ffi::c_int;
+use std::fmt::{Display, Formatter};
+
+use rusty_php_sys::zend::errors::{
+    E_ALL, E_COMPILE_WARNING, E_CORE_WARNING, E_DEPRECATED, E_FATAL_ERRORS, E_NOTICE, E_STRICT,
+    E_USER_DEPRECATED, E_USER_NOTICE, E_USER_WARNING, E_WARNING,
+};
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Severity {
+    Deprecated,
+    Notice,
+    Warning,
+    Error,
+}
+
+impl Severity {
+    /// The severity of an `E_*` level.
+    pub fn from_level(level: c_int) -> Self {
+        match level {
+            l if l & E_FATAL_ERRORS != 0 => Severity::Error,
+            l if l & (E_WARNING | E_CORE_WARNING | E_COMPILE_WARNING | E_USER_WARNING) != 0 => {
+                Severity::Warning
+            }
+            l if l & (E_NOTICE | E_USER_NOTICE | E_STRICT) != 0 => Severity::Notice,
+            l if l & (E_DEPRECATED | E_USER_DEPRECATED) != 0 => Severity::Deprecated,
+            _ => Severity::Error,
+        }
+    }
+}
+
+impl Display for Severity {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Severity::Deprecated => "Deprecated",
+            Severity::Notice => "Notice",
+            Severity::Warning => "Warning",
+            Severity::Error => "Fatal error",
+        })
+    }
+}
+
+/// A diagnostic raised by the engine, seen before any error handler or
+/// `error_reporting` setting applies.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Diagnostic {
+    level: c_int,
+    message: String,
+    file: Option<String>,
+    line: u32,
+}
+
+impl Diagnostic {
+    pub(crate) fn new(level: c_int, message: String, file: Option<String>, line: u32) -> Self {
+        Self {
+            level: level & E_ALL,
+            message,
+            file,
+            line,
+        }
+    }
+
+    /// The `E_*` level, such as `E_WARNING`.
+    pub fn level(&self) -> c_int {
+        self.level
+    }
+
+    pub fn severity(&self) -> Severity {
+        Severity::from_level(self.level)
+    }
+
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+
+    /// The file being executed, `None` while the engine starts up.
+    pub fn file(&self) -> Option<&str> {
+        self.file.as_deref()
+    }
+
+    pub fn line(&self) -> u32 {
+        self.line
+    }
+}
+
+impl Display for Diagnostic {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "PHP {}:  {}", self.severity(), self.message)?;
+        if let Some(file) = &self.file {
+            write!(f, " in {} on line {}", file, self.line)?;
+        }
+        std::fmt::Result::Ok(())
+    }
+}
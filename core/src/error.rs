@@ -0,0 +1,146 @@
+//! Errors returned by rusty-php.
+
+use std::cell::RefCell;
+use std::fmt::{Display, Formatter};
+
+use crate::zend::exception::PhpException;
+
+/// A stage of the engine's lifecycle, or of running code in a request.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Stage {
+    /// `sapi_startup`, run by [`PhpInit::init`](crate::PhpInit::init).
+    SapiStartup,
+    /// `php_module_startup`, including the `MINIT` of every extension.
+    ModuleStartup,
+    /// `php_request_startup`, including the `RINIT` of every extension.
+    RequestStartup,
+    Eval,
+    /// Executing a script file.
+    Execute,
+    /// Calling a PHP function or method.
+    Call,
+}
+
+impl Display for Stage {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Stage::SapiStartup => "sapi_startup",
+            Stage::ModuleStartup => "php_module_startup",
+            Stage::RequestStartup => "php_request_startup",
+            Stage::Eval => "eval",
+            Stage::Execute => "script execution",
+            Stage::Call => "call",
+        })
+    }
+}
+
+#[derive(Clone, Debug)]
+pub enum Error {
+    /// A stage failed. `last_error` is the last error PHP logged, which
+    /// usually explains why.
+    Stage {
+        stage: Stage,
+        last_error: Option<String>,
+    },
+    /// The engine bailed out of a stage after a fatal error or a timeout,
+    /// explained by `last_error`. Only shutting down is safe afterwards.
+    Bailout {
+        stage: Stage,
+        last_error: Option<String>,
+    },
+    /// PHP code threw an exception that nothing caught.
+    Exception(PhpException),
+    /// An exception was thrown into the running PHP code, which handles it
+    /// once the Rust code returns.
+    Thrown,
+    /// PHP is running already, and can only run once at a time.
+    Running,
+    /// A failure reported by Rust code, such as a SAPI callback or an
+    /// extension hook.
+    Custom(String),
+}
+
+impl Error {
+    /// A failure of `stage`, explained by the last error PHP logged.
+    pub(crate) fn stage(stage: Stage) -> Self {
+        Error::Stage {
+            stage,
+            last_error: LAST_ERROR.with(|last| last.borrow_mut().take()),
+        }
+    }
+
+    /// A bailout of `stage`, explained by the last error PHP reported.
+    pub(crate) fn bailout(stage: Stage) -> Self {
+        Error::Bailout {
+            stage,
+            last_error: LAST_ERROR.with(|last| last.borrow_mut().take()),
+        }
+    }
+
+    pub fn custom<M>(message: M) -> Self
+    where
+        M: Into<String>,
+    {
+        Error::Custom(message.into())
+    }
+
+    pub fn exception(&self) -> Option<&PhpException> {
+        match self {
+            Error::Exception(exception) => Some(exception),
+            _ => None,
+        }
+    }
+}
+
+impl Display for Error {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Error::Stage {
+                stage,
+                last_error: Some(last_error),
+            } => write!(f, "{} failed: {}", stage, last_error),
+            Error::Stage { stage, .. } => write!(f, "{} failed", stage),
+            Error::Bailout {
+                stage,
+                last_error: Some(last_error),
+            } => write!(f, "PHP bailed out of {}: {}", stage, last_error),
+            Error::Bailout { stage, .. } => write!(f, "PHP bailed out of {}", stage),
+            Error::Exception(exception) => write!(f, "Uncaught {}", exception),
+            Error::Thrown => f.write_str("an exception was thrown to PHP"),
+            Error::Running => f.write_str("PHP is already running in this process"),
+            Error::Custom(message) => f.write_str(message),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Exception(exception) => Some(exception),
+            _ => None,
+        }
+    }
+}
+
+impl From<PhpException> for Error {
+    fn from(value: PhpException) -> Self {
+        Error::Exception(value)
+    }
+}
+
+thread_local! {
+    /// The last message PHP logged on this thread, until a failing stage
+    /// takes it.
+    static LAST_ERROR: RefCell<Option<String>> = const { RefCell::new(None) };
+}
+
+pub(crate) fn record_last_error(message: &[u8]) {
+    let message = String::from_utf8_lossy(message).trim_end().to_string();
+    LAST_ERROR.with(|last| *last.borrow_mut() = Some(message));
+}
+
+/// Forgets the last error, so a failing stage is not explained by an
+/// earlier message.
+pub(crate) fn clear_last_error() {
+    LAST_ERROR.with(|last| last.borrow_mut().take());
+}
@@ -0,0 +1,241 @@
+//! PHP extensions written in Rust, taking part in the module and request
+//! lifecycle like extensions compiled into PHP.
+
+use std::cell::Cell;
+use std::collections::BTreeMap;
+use std::ffi::{c_int, CString};
+use std::panic::{catch_unwind, AssertUnwindSafe};
+use std::ptr;
+use std::sync::{Arc, PoisonError, RwLock};
+
+use rusty_php_sys::ext::standard::info::{
+    php_info_print_table_end, php_info_print_table_header, php_info_print_table_row,
+    php_info_print_table_start,
+};
+use rusty_php_sys::zend::api::zend_register_internal_module;
+use rusty_php_sys::zend::modules::ZendModuleEntry;
+use rusty_php_sys::zend::ZendResult;
+use rusty_php_sys::{php_register_internal_extensions, php_register_internal_extensions_func};
+
+use crate::sapi::create_cstring;
+use crate::zend::function::{Function, FunctionTable};
+use crate::{Err, Ok, Result};
+
+/// A PHP extension. Every hook runs on the thread driving the engine and
+/// failing one fails the corresponding startup or shutdown.
+pub trait Extension: Send + Sync {
+    /// The name shown by `get_loaded_extensions()` and `phpinfo()`.
+    fn name(&self) -> &str;
+
+    fn version(&self) -> &str;
+
+    /// Global functions registered along with the extension.
+    fn functions(&self) -> Vec<Function> {
+        Vec::new()
+    }
+
+    /// `MINIT`, called once while the engine starts up.
+    fn module_startup(&self) -> Result<()> {
+        Ok(())
+    }
+
+    /// `MSHUTDOWN`, called once while the engine shuts down.
+    fn module_shutdown(&self) -> Result<()> {
+        Ok(())
+    }
+
+    /// `RINIT`, called at the start of every request.
+    fn request_startup(&self) -> Result<()> {
+        Ok(())
+    }
+
+    /// `RSHUTDOWN`, called at the end of every request.
+    fn request_shutdown(&self) -> Result<()> {
+        Ok(())
+    }
+
+    /// `MINFO`, filling in the section of the extension in `phpinfo()`.
+    fn info(&self, info: &mut ModuleInfo) {
+        info.row("Version", self.version());
+    }
+}
+
+/// The table of an extension's section in `phpinfo()`.
+pub struct ModuleInfo {
+    _private: (),
+}
+
+impl ModuleInfo {
+    pub fn header(&mut self, name: &str, value: &str) {
+        let name = create_cstring(name.as_bytes());
+        let value = create_cstring(value.as_bytes());
+        unsafe { php_info_print_table_header(2, name.as_ptr(), value.as_ptr()) };
+    }
+
+    pub fn row(&mut self, name: &str, value: &str) {
+        let name = create_cstring(name.as_bytes());
+        let value = create_cstring(value.as_bytes());
+        unsafe { php_info_print_table_row(2, name.as_ptr(), value.as_ptr()) };
+    }
+}
+
+/// Running extensions, by module number.
+static EXTENSIONS: RwLock<BTreeMap<c_int, Arc<dyn Extension>>> = RwLock::new(BTreeMap::new());
+
+/// Forgets the extensions left running, such as those whose `MSHUTDOWN` never
+/// ran, once PHP is shut down.
+pub(crate) fn unregister_extensions() {
+    EXTENSIONS
+        .write()
+        .unwrap_or_else(PoisonError::into_inner)
+        .clear();
+}
+
+thread_local! {
+    /// The extensions of the engine being started on this thread.
+    static STARTING: Cell<*mut [ExtensionModule]> = const { Cell::new(ptr::slice_from_raw_parts_mut(ptr::null_mut(), 0)) };
+}
+
+fn dispatch<F>(module_number: c_int, f: F) -> ZendResult
+where
+    F: FnOnce(&dyn Extension) -> Result<()>,
+{
+    let extension = match EXTENSIONS
+        .read()
+        .unwrap_or_else(PoisonError::into_inner)
+        .get(&module_number)
+    {
+        Some(extension) => Arc::clone(extension),
+        _ => return ZendResult::Failure,
+    };
+
+    match catch_unwind(AssertUnwindSafe(|| f(&*extension))) {
+        std::result::Result::Ok(Err(e)) => {
+            tracing::error!("Extension {} failed: {}", extension.name(), e);
+            ZendResult::Failure
+        }
+        std::result::Result::Ok(Ok(())) => ZendResult::Success,
+        std::result::Result::Err(_) => {
+            tracing::error!("Extension {} panicked", extension.name());
+            ZendResult::Failure
+        }
+    }
+}
+
+unsafe extern "C" fn module_startup(_ty: c_int, module_number: c_int) -> ZendResult {
+    dispatch(module_number, |extension| extension.module_startup())
+}
+
+unsafe extern "C" fn module_shutdown(_ty: c_int, module_number: c_int) -> ZendResult {
+    let ret = dispatch(module_number, |extension| extension.module_shutdown());
+    EXTENSIONS
+        .write()
+        .unwrap_or_else(PoisonError::into_inner)
+        .remove(&module_number);
+    ret
+}
+
+unsafe extern "C" fn request_startup(_ty: c_int, module_number: c_int) -> ZendResult {
+    dispatch(module_number, |extension| extension.request_startup())
+}
+
+unsafe extern "C" fn request_shutdown(_ty: c_int, module_number: c_int) -> ZendResult {
+    dispatch(module_number, |extension| extension.request_shutdown())
+}
+
+unsafe extern "C" fn module_info(module: *mut ZendModuleEntry) {
+    php_info_print_table_start();
+    dispatch((*module).module_number, |extension| {
+        extension.info(&mut ModuleInfo { _private: () });
+        Ok(())
+    });
+    php_info_print_table_end();
+}
+
+/// Registers the extensions right after the ones compiled into PHP, so they
+/// are started, sorted and collected for request hooks like any other.
+unsafe extern "C" fn register_extensions() -> ZendResult {
+    if php_register_internal_extensions() == ZendResult::Failure {
+        return ZendResult::Failure;
+    }
+
+    for module in &mut *STARTING.with(Cell::get) {
+        let entry = zend_register_internal_module(&mut module.entry);
+        if entry.is_null() {
+            tracing::error!("Registering extension {} failed", module.extension.name());
+            return ZendResult::Failure;
+        }
+
+        EXTENSIONS
+            .write()
+            .unwrap_or_else(PoisonError::into_inner)
+            .insert((*entry).module_number, Arc::clone(&module.extension));
+    }
+    ZendResult::Success
+}
+
+struct ExtensionModule {
+    extension: Arc<dyn Extension>,
+    entry: ZendModuleEntry,
+    _name: CString,
+    _version: CString,
+    _functions: FunctionTable,
+}
+
+/// The module entries of the extensions, which the engine points into until
+/// it shuts down.
+pub(crate) struct ExtensionTable {
+    modules: Vec<ExtensionModule>,
+}
+
+impl ExtensionTable {
+    pub(crate) fn new(extensions: Vec<Box<dyn Extension>>) -> Self {
+        let modules = extensions
+            .into_iter()
+            .map(|extension| {
+                let name = create_cstring(extension.name().as_bytes());
+                let version = create_cstring(extension.version().as_bytes());
+                let functions = FunctionTable::new(None, extension.functions());
+
+                let mut entry = ZendModuleEntry::new(name.as_ptr(), version.as_ptr());
+                entry.functions = functions.as_ptr();
+                entry.module_startup_func = Some(module_startup);
+                entry.module_shutdown_func = Some(module_shutdown);
+                entry.request_startup_func = Some(request_startup);
+                entry.request_shutdown_func = Some(request_shutdown);
+                entry.info_func = Some(module_info);
+
+                ExtensionModule {
+                    extension: Arc::from(extension),
+                    entry,
+                    _name: name,
+                    _version: version,
+                    _functions: functions,
+                }
+            })
+            .collect();
+
+        Self { modules }
+    }
+
+    /// Runs `f`, which starts up the modules, with the extensions registered
+    /// along with the ones compiled into PHP.
+    pub(crate) fn startup<F, R>(&mut self, f: F) -> R
+    where
+        F: FnOnce() -> R,
+    {
+        if self.modules.is_empty() {
+            return f();
+        }
+
+        STARTING.with(|starting| starting.set(self.modules.as_mut_slice()));
+        let register = unsafe { php_register_internal_extensions_func };
+        unsafe { php_register_internal_extensions_func = register_extensions };
+
+        let ret = f();
+
+        unsafe { php_register_internal_extensions_func = register };
+        STARTING.with(|starting| starting.set(ptr::slice_from_raw_parts_mut(ptr::null_mut(), 0)));
+        ret
+    }
+}
@@ -1,74 +1,223 @@
 #![feature(c_variadic)]
 #![feature(try_trait_v2)]
 
+mod module;
 mod result;
 
 pub mod callback;
+pub mod diagnostic;
+pub mod error;
+pub mod extension;
 pub mod ffi;
+#[cfg(feature = "zts")]
+pub mod pool;
+pub mod request;
 pub mod sapi;
 pub mod test;
 pub mod zend;
 
-use std::error::Error;
+use std::ffi::c_char;
+#[cfg(feature = "zts")]
+use std::ffi::c_int;
+use std::path::Path;
 use std::ptr::null_mut;
-use std::result::Result as StdResult;
-use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
 
+#[cfg(feature = "macros")]
+pub use rusty_php_macros::{php_class, php_function, php_methods, php_module};
 pub use rusty_php_sys as sys;
 
+use crate::callback::unregister_global_callback;
+pub use crate::error::Error;
+use crate::error::{clear_last_error, Stage};
+use crate::extension::{unregister_extensions, Extension, ExtensionTable};
+use crate::module::CoreModule;
+#[cfg(feature = "zts")]
+use crate::pool::PhpPool;
+use crate::request::RequestInfo;
+use crate::result::guard;
 pub use crate::result::{Err, Ok, Result};
 use crate::sapi::{Sapi, SapiExt};
 use crate::sys::sapi::SapiModuleStruct;
+use crate::sys::zend::bailout::zend_try;
+use crate::zend::class::{unregister_classes, Class, ClassDef, ClassTable};
+use crate::zend::convert::{IntoArgs, IntoZval};
+use crate::zend::function::{unregister_functions, Function, FunctionTable};
+use crate::zend::zval::OwnedZval;
 
-pub struct PhpRequest {
-    inner: PhpModule,
+/// A running request, owned by the module, or by a pool worker for pooled
+/// requests. Dropping it shuts it down, along with what owns it.
+pub struct PhpRequest<M = PhpModule> {
+    inner: Option<M>,
+    info: RequestInfo,
+    _argv: Vec<*mut c_char>,
 }
 
-impl PhpRequest {
-    fn startup(inner: PhpModule) -> Result<Self> {
-        Result::from(unsafe { sys::php_request_startup() })?;
+impl<M> PhpRequest<M> {
+    /// Starts a request owned by a pool worker.
+    #[cfg(feature = "zts")]
+    fn startup(inner: M, info: RequestInfo) -> Result<Self> {
+        let mut argv = Vec::new();
+        request_startup(&info, &mut argv)?;
+
+        Ok(Self {
+            inner: Some(inner),
+            info,
+            _argv: argv,
+        })
+    }
+
+    /// What the request was started with.
+    pub fn info(&self) -> &RequestInfo {
+        &self.info
+    }
 
-        Ok(Self { inner })
+    /// Calls a PHP function or any other callable with the given arguments.
+    /// Fails with the exception thrown by the callable, if any.
+    pub fn call<F, A>(&self, callable: F, args: A) -> Result<OwnedZval>
+    where
+        F: IntoZval,
+        A: IntoArgs,
+    {
+        zend::call::call(callable, args)
     }
 
+    /// Evaluates PHP code as the body of `return <code>;`.
+    pub fn eval(&self, code: &str) -> Result<OwnedZval> {
+        self.eval_named(code, "eval()'d code")
+    }
+
+    /// Evaluates PHP code like [`eval`](Self::eval), naming it `name` in
+    /// error messages and traces, such as `Command line code`.
+    pub fn eval_named(&self, code: &str, name: &str) -> Result<OwnedZval> {
+        zend::call::eval(code, name)
+    }
+
+    /// Executes a script file as the primary script of the request.
+    pub fn execute<P>(&self, path: P) -> Result<()>
+    where
+        P: AsRef<Path>,
+    {
+        zend::call::execute(path)
+    }
+
+    /// Shuts the request down, leaving the module ready for the next one.
+    /// Superglobals, output buffers, headers and `SG(request_info)` start
+    /// over with each request.
     #[must_use]
-    pub fn shutdown(self) -> PhpModule {
-        unsafe {
-            sys::php_request_shutdown(null_mut());
+    pub fn shutdown(mut self) -> M {
+        request_shutdown();
+        self.inner.take().unwrap()
+    }
+}
+
+impl<M> Drop for PhpRequest<M> {
+    fn drop(&mut self) {
+        if self.inner.is_some() {
+            request_shutdown();
         }
-        self.inner
     }
+}
+
+/// Starts a request with `info`, whose argument pointers are kept in `argv`.
+fn request_startup(info: &RequestInfo, argv: &mut Vec<*mut c_char>) -> Result<()> {
+    unsafe { info.install(argv) };
+
+    clear_last_error();
+    let started = guard(Stage::RequestStartup, || unsafe {
+        sys::php_request_startup()
+    });
+    if started.is_err() {
+        unsafe { RequestInfo::uninstall() };
+    }
+    started
+}
 
+fn request_shutdown() {
+    if unsafe { zend_try(|| sys::php_request_shutdown(null_mut())) }.is_none() {
+        tracing::error!("PHP bailed out of php_request_shutdown");
+    }
+    unsafe { RequestInfo::uninstall() };
+}
+
+impl PhpRequest {
     pub fn shutdown_all(self) {
         self.shutdown().shutdown_all()
     }
 }
 
+/// The started module. Dropping it shuts it down, and PHP along with it.
 pub struct PhpModule {
-    inner: Php,
+    inner: Option<Php>,
 }
 
 impl PhpModule {
-    fn startup(inner: Php) -> Result<Self> {
-        Result::<()>::from(unsafe {
-            sys::php_module_startup(
-                Arc::into_raw(Arc::clone(&inner.sapi_module)) as *mut SapiModuleStruct,
-                null_mut(),
-            )
-        })?;
+    fn startup(mut inner: Php) -> Result<Self> {
+        let sapi_module: *mut SapiModuleStruct = &mut *inner.sapi_module;
+        let (module, classes) = (&mut inner.module, &inner.classes);
+        clear_last_error();
+        // Only the engine call is guarded, so that the extensions and the
+        // module restore what they swapped in for it even if it bails out.
+        let started = inner.extensions.startup(|| {
+            module.startup(classes, |module| {
+                let startup = || unsafe { sys::php_module_startup(sapi_module, module) };
 
-        Ok(Self { inner })
+                // With ZTS the executor globals, and so `EG(bailout)`, only
+                // exist once `zend_startup` has run, so a bailout cannot be
+                // caught here.
+                #[cfg(not(feature = "zts"))]
+                let started = guard(Stage::ModuleStartup, startup);
+                #[cfg(feature = "zts")]
+                let started = crate::result::check(startup(), Stage::ModuleStartup);
+
+                started
+            })
+        });
+
+        match started {
+            Ok(()) => Ok(Self { inner: Some(inner) }),
+            Err(e) => {
+                inner.shutdown();
+                Err(e)
+            }
+        }
     }
 
     #[must_use]
     pub fn startup_request(self) -> Result<PhpRequest> {
-        PhpRequest::startup(self)
+        self.startup_request_with(RequestInfo::new())
+    }
+
+    /// Starts a request with its arguments, script path and request info.
+    /// Requests can be started and shut down in turn for as long as the
+    /// module is up. If one fails to start, the module is shut down, as PHP's
+    /// own SAPIs do.
+    #[must_use]
+    pub fn startup_request_with(self, info: RequestInfo) -> Result<PhpRequest> {
+        let mut argv = Vec::new();
+        match request_startup(&info, &mut argv) {
+            Ok(()) => Ok(PhpRequest {
+                inner: Some(self),
+                info,
+                _argv: argv,
+            }),
+            Err(e) => {
+                self.shutdown_all();
+                Err(e)
+            }
+        }
+    }
+
+    /// Starts `workers` threads serving requests in parallel.
+    #[cfg(feature = "zts")]
+    pub fn into_pool(self, workers: usize) -> PhpPool {
+        PhpPool::start(self, workers)
     }
 
     #[must_use]
-    pub fn shutdown(self) -> Php {
-        unsafe { sys::php_module_shutdown() };
-        self.inner
+    pub fn shutdown(mut self) -> Php {
+        module_shutdown();
+        self.inner.take().unwrap()
     }
 
     pub fn shutdown_all(self) {
@@ -76,28 +225,62 @@ impl PhpModule {
     }
 }
 
+impl Drop for PhpModule {
+    fn drop(&mut self) {
+        if self.inner.is_some() {
+            module_shutdown();
+        }
+    }
+}
+
+fn module_shutdown() {
+    if unsafe { zend_try(|| sys::php_module_shutdown()) }.is_none() {
+        tracing::error!("PHP bailed out of php_module_shutdown");
+    }
+}
+
+/// Whether PHP is running in this process, where it can only run once at a
+/// time.
+static RUNNING: AtomicBool = AtomicBool::new(false);
+
+/// PHP with its SAPI started. Dropping it shuts it down, like
+/// [`shutdown`](Self::shutdown).
 pub struct Php {
-    sapi_module: Arc<SapiModuleStruct>,
+    sapi_module: Box<SapiModuleStruct>,
+    _functions: FunctionTable,
+    module: CoreModule,
+    classes: ClassTable,
+    extensions: ExtensionTable,
 }
 
 impl Php {
-    fn startup<S>(sapi: S) -> StdResult<Self, Box<dyn Error>>
+    fn startup<S>(
+        sapi: S,
+        functions: Vec<Function>,
+        classes: Vec<ClassDef>,
+        extensions: Vec<Box<dyn Extension>>,
+    ) -> Result<Self>
     where
         S: SapiExt,
     {
-        #[cfg(feature = "zts")]
-        unsafe {
-            sys::php_tsrm_startup()
-        };
-
         sapi.register();
 
-        let sapi_module = Arc::new(sapi.into_raw());
-        unsafe {
-            sys::sapi_startup(Arc::into_raw(Arc::clone(&sapi_module)) as *mut SapiModuleStruct)
-        };
+        let functions = FunctionTable::new(None, functions);
+        let classes = ClassTable::new(classes);
+        let extensions = ExtensionTable::new(extensions);
+        let mut sapi_module = sapi.into_raw();
+        sapi_module.additional_functions = functions.as_ptr();
 
-        StdResult::Ok(Self { sapi_module })
+        let mut sapi_module = Box::new(sapi_module);
+        unsafe { sys::sapi_startup(&mut *sapi_module) };
+
+        Ok(Self {
+            sapi_module,
+            _functions: functions,
+            module: CoreModule::new(),
+            classes,
+            extensions,
+        })
     }
 
     #[must_use]
@@ -105,8 +288,29 @@ impl Php {
         PhpModule::startup(self)
     }
 
+    /// Shuts PHP down, releasing the SAPI and its callback along with the
+    /// registered functions, classes and extensions, so that it can be
+    /// started again.
     pub fn shutdown(self) {
-        unsafe { sys::sapi_shutdown() };
+        drop(self)
+    }
+}
+
+impl Drop for Php {
+    fn drop(&mut self) {
+        unsafe {
+            sys::sapi_shutdown();
+            sapi::release_raw(&self.sapi_module);
+
+            #[cfg(feature = "zts")]
+            sys::tsrm::tsrm_shutdown();
+        }
+
+        unregister_functions();
+        unregister_classes();
+        unregister_extensions();
+        unregister_global_callback();
+        RUNNING.store(false, Ordering::Release);
     }
 }
 
@@ -115,6 +319,11 @@ where
     S: Sapi,
 {
     sapi: S,
+    functions: Vec<Function>,
+    classes: Vec<ClassDef>,
+    extensions: Vec<Box<dyn Extension>>,
+    #[cfg(feature = "zts")]
+    expected_threads: c_int,
 }
 
 impl<S> PhpInit<S>
@@ -122,10 +331,60 @@ where
     S: Sapi,
 {
     pub fn new(sapi: S) -> Self {
-        Self { sapi }
+        Self {
+            sapi,
+            functions: Vec::new(),
+            classes: Vec::new(),
+            extensions: Vec::new(),
+            #[cfg(feature = "zts")]
+            expected_threads: 1,
+        }
+    }
+
+    /// Sizes the thread-safe resource tables for `threads` threads running
+    /// PHP, such as the workers of a [`PhpPool`] and the main thread.
+    #[cfg(feature = "zts")]
+    pub fn threads(mut self, threads: usize) -> Self {
+        self.expected_threads = threads as c_int;
+        self
+    }
+
+    /// Registers a Rust function as a global PHP function, available from
+    /// module startup on.
+    pub fn function(mut self, function: Function) -> Self {
+        self.functions.push(function);
+        self
+    }
+
+    /// Registers a class defined in Rust. Classes are declared in order, so a
+    /// parent must be registered before the classes extending it.
+    pub fn class<T>(mut self, class: Class<T>) -> Self {
+        self.classes.push(class.into());
+        self
     }
 
-    pub fn init(self) -> StdResult<Php, Box<dyn Error>> {
-        Php::startup(self.sapi)
+    /// Registers an extension, started along with the ones compiled into PHP.
+    pub fn extension<E>(mut self, extension: E) -> Self
+    where
+        E: Extension + 'static,
+    {
+        self.extensions.push(Box::new(extension));
+        self
+    }
+
+    /// Starts PHP up. Fails with [`Error::Running`] while it runs already,
+    /// until it is shut down.
+    pub fn init(self) -> Result<Php> {
+        if RUNNING.swap(true, Ordering::AcqRel) {
+            return Err(Error::Running);
+        }
+
+        #[cfg(feature = "zts")]
+        if !unsafe { sys::php_tsrm_startup_ex(self.expected_threads) } {
+            RUNNING.store(false, Ordering::Release);
+            return Err(Error::stage(Stage::SapiStartup));
+        }
+
+        Php::startup(self.sapi, self.functions, self.classes, self.extensions)
     }
 }
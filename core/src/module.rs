@@ -0,0 +1,70 @@
+//! The rusty-php module, passed to `php_module_startup` for what can only be
+//! done from `MINIT`: registering the engine observers and the classes.
+
+use std::cell::Cell;
+use std::ffi::{c_char, c_int};
+use std::ptr;
+
+use rusty_php_sys::zend::modules::ZendModuleEntry;
+use rusty_php_sys::zend::observer::{zend_observer_error_register, zend_observer_fcall_register};
+use rusty_php_sys::zend::ZendResult;
+
+use crate::callback::listeners::on_error;
+use crate::zend::call::observe_fcall;
+use crate::zend::class::ClassTable;
+use crate::{Err, Ok};
+
+thread_local! {
+    /// The classes of the module being started on this thread, for `MINIT`.
+    static STARTING: Cell<*const ClassTable> = const { Cell::new(ptr::null()) };
+}
+
+/// Registers the observers of the engine. With a call observer registered,
+/// every call of a PHP function checks for handlers, though only top-level
+/// code gets one, to tell `exit()` apart from a fatal error in
+/// [`execute`](crate::zend::call::execute).
+unsafe fn register_observers() {
+    zend_observer_error_register(on_error);
+    zend_observer_fcall_register(observe_fcall);
+}
+
+unsafe extern "C" fn module_startup(_ty: c_int, _module_number: c_int) -> ZendResult {
+    register_observers();
+
+    let classes = STARTING.with(Cell::get);
+    match (*classes).register() {
+        Ok(()) => ZendResult::Success,
+        Err(e) => {
+            tracing::error!("{}", e);
+            ZendResult::Failure
+        }
+    }
+}
+
+pub(crate) struct CoreModule {
+    entry: ZendModuleEntry,
+}
+
+impl CoreModule {
+    pub(crate) fn new() -> Self {
+        let mut entry = ZendModuleEntry::new(
+            c"rusty-php".as_ptr(),
+            concat!(env!("CARGO_PKG_VERSION"), "\0").as_ptr() as *const c_char,
+        );
+        entry.module_startup_func = Some(module_startup);
+
+        Self { entry }
+    }
+
+    /// Runs `f` with the module entry to pass to `php_module_startup`, which
+    /// registers `classes`.
+    pub(crate) fn startup<F, R>(&mut self, classes: &ClassTable, f: F) -> R
+    where
+        F: FnOnce(*mut ZendModuleEntry) -> R,
+    {
+        STARTING.with(|starting| starting.set(classes));
+        let ret = f(&mut self.entry);
+        STARTING.with(|starting| starting.set(ptr::null()));
+        ret
+    }
+}
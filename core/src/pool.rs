@@ -0,0 +1,160 @@
+//! A pool of threads serving requests in parallel, for ZTS builds of PHP.
+
+use std::panic::{catch_unwind, AssertUnwindSafe};
+use std::sync::mpsc::{channel, Receiver, Sender};
+use std::sync::{Arc, Mutex};
+use std::thread::{self, JoinHandle};
+
+use crate::request::RequestInfo;
+use crate::sys::tsrm::{ts_free_thread, ts_resource};
+use crate::{Err, Error, Ok, PhpModule, PhpRequest, Result};
+
+enum Message {
+    Job(Box<dyn FnOnce() + Send>),
+    Stop,
+}
+
+/// The pool thread a request runs on, which owns it in place of a module.
+pub struct Worker {
+    _private: (),
+}
+
+/// Threads serving requests of a module in parallel, each with its own
+/// globals. Jobs are submitted through a [`PoolHandle`]. Dropping the pool
+/// stops the workers and shuts the module down.
+pub struct PhpPool {
+    module: Option<PhpModule>,
+    jobs: Sender<Message>,
+    workers: Vec<JoinHandle<()>>,
+}
+
+impl PhpPool {
+    pub(crate) fn start(module: PhpModule, workers: usize) -> Self {
+        let (jobs, queue) = channel();
+        let queue = Arc::new(Mutex::new(queue));
+
+        let workers = (0..workers)
+            .map(|i| {
+                let queue = Arc::clone(&queue);
+                thread::Builder::new()
+                    .name(format!("php-worker-{}", i))
+                    .spawn(move || work(&queue))
+                    .expect("failed to spawn a PHP worker")
+            })
+            .collect();
+
+        Self {
+            module: Some(module),
+            jobs,
+            workers,
+        }
+    }
+
+    pub fn handle(&self) -> PoolHandle {
+        PoolHandle {
+            jobs: self.jobs.clone(),
+        }
+    }
+
+    /// Runs the jobs submitted so far and stops the workers, leaving the
+    /// module to shut down. Jobs submitted afterwards fail.
+    #[must_use]
+    pub fn shutdown(mut self) -> PhpModule {
+        self.stop();
+        self.module.take().unwrap()
+    }
+
+    pub fn shutdown_all(self) {
+        self.shutdown().shutdown_all()
+    }
+
+    fn stop(&mut self) {
+        for _ in &self.workers {
+            let _ = self.jobs.send(Message::Stop);
+        }
+        for worker in self.workers.drain(..) {
+            if worker.join().is_err() {
+                tracing::error!("A PHP worker panicked");
+            }
+        }
+    }
+}
+
+impl Drop for PhpPool {
+    fn drop(&mut self) {
+        self.stop();
+    }
+}
+
+fn work(queue: &Mutex<Receiver<Message>>) {
+    // Allocates the globals of this thread, and points the TSRM cache at them.
+    unsafe { ts_resource(0) };
+
+    loop {
+        let message = match queue.lock() {
+            std::result::Result::Ok(queue) => queue.recv(),
+            std::result::Result::Err(_) => break,
+        };
+
+        match message {
+            std::result::Result::Ok(Message::Job(job)) => job(),
+            _ => break,
+        }
+    }
+
+    unsafe { ts_free_thread() };
+}
+
+/// Submits jobs to a [`PhpPool`], from any thread.
+#[derive(Clone)]
+pub struct PoolHandle {
+    jobs: Sender<Message>,
+}
+
+impl PoolHandle {
+    /// Runs `f` in a request started with `info`, on the next free worker.
+    pub fn submit<F, R>(&self, info: RequestInfo, f: F) -> JobHandle<R>
+    where
+        F: FnOnce(&PhpRequest<Worker>) -> R + Send + 'static,
+        R: Send + 'static,
+    {
+        let (result, receiver) = channel();
+        let job = Box::new(move || {
+            let _ = result.send(run(info, f));
+        });
+
+        // Once the pool is gone the job is dropped unrun, which `join` reports.
+        let _ = self.jobs.send(Message::Job(job));
+        JobHandle { result: receiver }
+    }
+}
+
+fn run<F, R>(info: RequestInfo, f: F) -> Result<R>
+where
+    F: FnOnce(&PhpRequest<Worker>) -> R,
+{
+    let request = PhpRequest::startup(Worker { _private: () }, info)?;
+    let ret = catch_unwind(AssertUnwindSafe(|| f(&request)));
+    let _ = request.shutdown();
+
+    match ret {
+        std::result::Result::Ok(ret) => Ok(ret),
+        std::result::Result::Err(_) => Err(Error::custom("PHP job panicked")),
+    }
+}
+
+/// The result of a job submitted to a [`PhpPool`].
+pub struct JobHandle<R> {
+    result: Receiver<Result<R>>,
+}
+
+impl<R> JobHandle<R> {
+    /// Waits for the job to finish. Fails if the request could not start, the
+    /// job panicked, or the pool shut down before running it.
+    pub fn join(self) -> Result<R> {
+        match self.result.recv() {
+            std::result::Result::Ok(ret) => ret,
+            std::result::Result::Err(_) => Err(Error::custom("PHP pool shut down")),
+        }
+    }
+}
@@ -0,0 +1,136 @@
+//! Per-request setup, read by the engine from `SG(request_info)`.
+
+use std::ffi::{c_char, c_void, CString};
+use std::os::unix::ffi::OsStrExt;
+use std::path::Path;
+use std::ptr::{addr_of_mut, null_mut};
+
+use crate::callback::Callback;
+use crate::sapi::create_cstring;
+use crate::sys::sapi::sg;
+use crate::sys::zend::ZendLong;
+
+/// What a request starts with: the arguments of a command line script, or the
+/// method, URI and body of a web request. Anything not set is left empty, so
+/// nothing carries over from the previous request.
+#[derive(Clone, Debug, Default)]
+pub struct RequestInfo {
+    argv: Vec<CString>,
+    script_path: Option<CString>,
+    method: Option<CString>,
+    uri: Option<CString>,
+    query_string: Option<CString>,
+    content_type: Option<CString>,
+    content_length: ZendLong,
+    callback: Option<Box<Callback>>,
+}
+
+impl RequestInfo {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The arguments of the script, starting with its name, as seen in
+    /// `$argv` and `$_SERVER['argv']`.
+    pub fn argv<I, A>(mut self, argv: I) -> Self
+    where
+        I: IntoIterator<Item = A>,
+        A: AsRef<[u8]>,
+    {
+        self.argv = argv
+            .into_iter()
+            .map(|arg| create_cstring(arg.as_ref()))
+            .collect();
+        self
+    }
+
+    /// The path of the script the request runs.
+    pub fn script_path<P>(mut self, path: P) -> Self
+    where
+        P: AsRef<Path>,
+    {
+        self.script_path = Some(create_cstring(path.as_ref().as_os_str().as_bytes()));
+        self
+    }
+
+    /// The HTTP method, such as `GET`. A `HEAD` request sends no body.
+    pub fn method(mut self, method: &str) -> Self {
+        self.method = Some(create_cstring(method.as_bytes()));
+        self
+    }
+
+    pub fn uri(mut self, uri: &str) -> Self {
+        self.uri = Some(create_cstring(uri.as_bytes()));
+        self
+    }
+
+    /// The query string without the `?`, parsed into `$_GET`.
+    pub fn query_string(mut self, query_string: &str) -> Self {
+        self.query_string = Some(create_cstring(query_string.as_bytes()));
+        self
+    }
+
+    /// The type and length of the request body, read through
+    /// [`on_read_post`](crate::callback::SapiCallback::on_read_post).
+    pub fn content(mut self, content_type: &str, content_length: usize) -> Self {
+        self.content_type = Some(create_cstring(content_type.as_bytes()));
+        self.content_length = content_length as ZendLong;
+        self
+    }
+
+    /// Handles the output, headers and body of this request, and its other
+    /// SAPI callbacks, in place of the callback of the SAPI. The engine only
+    /// reads the body and cookies of requests with their own callback.
+    pub fn callback(mut self, callback: Callback) -> Self {
+        self.callback = Some(Box::new(callback));
+        self
+    }
+
+    /// The callback of the running request, set as `SG(server_context)`.
+    pub(crate) unsafe fn current_callback() -> Option<&'static Callback> {
+        (sg!(server_context) as *const Callback).as_ref()
+    }
+
+    /// Points `SG(request_info)` at this info, which must outlive the request.
+    /// The pointers to the arguments are pushed to `argv`, which must too.
+    pub(crate) unsafe fn install(&self, argv: &mut Vec<*mut c_char>) {
+        fn ptr(s: &Option<CString>) -> *mut c_char {
+            s.as_ref().map_or(null_mut(), |s| s.as_ptr() as *mut c_char)
+        }
+
+        argv.clear();
+        argv.extend(self.argv.iter().map(|arg| arg.as_ptr() as *mut c_char));
+
+        let info = addr_of_mut!(sg!(request_info));
+        (*info).argc = argv.len() as _;
+        (*info).argv = match argv.is_empty() {
+            true => null_mut(),
+            _ => argv.as_mut_ptr(),
+        };
+        (*info).path_translated = ptr(&self.script_path);
+        (*info).request_method = ptr(&self.method);
+        (*info).request_uri = ptr(&self.uri);
+        (*info).query_string = ptr(&self.query_string);
+        (*info).content_type = ptr(&self.content_type);
+        (*info).content_length = self.content_length;
+
+        sg!(server_context) = self.callback.as_deref().map_or(null_mut(), |callback| {
+            callback as *const Callback as *mut c_void
+        });
+    }
+
+    /// Clears what [`install`](Self::install) set, once the request is over.
+    pub(crate) unsafe fn uninstall() {
+        let info = addr_of_mut!(sg!(request_info));
+        (*info).argc = 0;
+        (*info).argv = null_mut();
+        (*info).path_translated = null_mut();
+        (*info).request_method = null_mut();
+        (*info).request_uri = null_mut();
+        (*info).query_string = null_mut();
+        (*info).content_type = null_mut();
+        (*info).content_length = 0;
+
+        sg!(server_context) = null_mut();
+    }
+}
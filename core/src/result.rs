@@ -3,19 +3,88 @@ use std::ffi::{c_int, c_uint};
 use std::ops::{ControlFlow, FromResidual, Try};
 
 pub use self::Result::{Err, Ok};
+use crate::error::{Error, Stage};
+use crate::sys::zend::bailout::zend_try;
 use crate::sys::zend::ZendResultCode;
+use crate::zend::exception::PhpException;
 
-#[derive(Copy, Clone)]
+#[derive(Clone, Debug)]
 pub enum Result<T> {
     Ok(T),
-    Err,
+    Err(Error),
+}
+
+/// Fails with a [`Stage`] error if the engine reported a failure.
+pub(crate) fn check(code: ZendResultCode, stage: Stage) -> Result<()> {
+    match code {
+        ZendResultCode::Success => Ok(()),
+        ZendResultCode::Failure => Err(Error::stage(stage)),
+    }
+}
+
+/// Runs `f`, which calls into the engine for `stage`, failing with
+/// [`Error::Bailout`] if the engine bails out of it.
+pub(crate) fn guard<F>(stage: Stage, f: F) -> Result<()>
+where
+    F: FnOnce() -> ZendResultCode,
+{
+    match unsafe { zend_try(f) } {
+        Some(code) => check(code, stage),
+        _ => Err(Error::bailout(stage)),
+    }
 }
 
 impl<T> Result<T> {
     pub fn unwrap(self) -> T {
         match self {
             Ok(v) => v,
-            Err => panic!("error occurred while communicating with PHP"),
+            Err(e) => panic!("error occurred while communicating with PHP: {}", e),
+        }
+    }
+
+    pub fn unwrap_err(self) -> Error {
+        match self {
+            Ok(_) => panic!("called `unwrap_err()` on an `Ok` value"),
+            Err(e) => e,
+        }
+    }
+
+    /// Returns the uncaught PHP exception, panicking on success or any
+    /// other error.
+    pub fn unwrap_exception(self) -> PhpException {
+        match self {
+            Err(Error::Exception(e)) => e,
+            Err(e) => panic!("expected an uncaught exception, got: {}", e),
+            Ok(_) => panic!("called `unwrap_exception()` on an `Ok` value"),
+        }
+    }
+
+    pub fn is_ok(&self) -> bool {
+        matches!(self, Ok(_))
+    }
+
+    pub fn is_err(&self) -> bool {
+        !self.is_ok()
+    }
+
+    pub fn ok(self) -> Option<T> {
+        match self {
+            Ok(v) => Some(v),
+            Err(_) => None,
+        }
+    }
+
+    pub fn err(self) -> Option<Error> {
+        match self {
+            Ok(_) => None,
+            Err(e) => Some(e),
+        }
+    }
+
+    pub fn into_std(self) -> std::result::Result<T, Error> {
+        match self {
+            Ok(v) => std::result::Result::Ok(v),
+            Err(e) => std::result::Result::Err(e),
         }
     }
 }
@@ -33,8 +102,11 @@ where
 }
 
 impl<T> FromResidual for Result<T> {
-    fn from_residual(_: <Self as Try>::Residual) -> Self {
-        Err
+    fn from_residual(residual: <Self as Try>::Residual) -> Self {
+        match residual {
+            Ok(v) => match v {},
+            Err(e) => Err(e),
+        }
     }
 }
 
@@ -51,16 +123,16 @@ impl<T> Try for Result<T> {
     fn branch(self) -> ControlFlow<Self::Residual, Self::Output> {
         match self {
             Ok(v) => ControlFlow::Continue(v),
-            Err => ControlFlow::Break(Err),
+            Err(e) => ControlFlow::Break(Err(e)),
         }
     }
 }
 
-impl From<c_int> for Result<()> {
-    fn from(value: c_int) -> Self {
+impl<T> From<std::result::Result<T, Error>> for Result<T> {
+    fn from(value: std::result::Result<T, Error>) -> Self {
         match value {
-            0 => Ok(()),
-            _ => Err,
+            std::result::Result::Ok(v) => Ok(v),
+            std::result::Result::Err(e) => Err(e),
         }
     }
 }
@@ -80,15 +152,6 @@ impl<T> From<Result<T>> for c_uint {
     }
 }
 
-impl From<ZendResultCode> for Result<()> {
-    fn from(value: ZendResultCode) -> Self {
-        match value {
-            ZendResultCode::Success => Ok(()),
-            ZendResultCode::Failure => Err,
-        }
-    }
-}
-
 impl<T> From<Result<T>> for ZendResultCode {
     fn from(value: Result<T>) -> Self {
         match value {
@@ -9,6 +9,16 @@ pub(crate) fn create_cstring(bytes: &[u8]) -> CString {
     unsafe { CString::from_vec_unchecked(bytes.to_vec()) }
 }
 
+/// Frees the strings of a module made by [`SapiExt::into_raw`], once the
+/// engine no longer uses it.
+pub(crate) unsafe fn release_raw(module: &SapiModuleStruct) {
+    for s in [module.name, module.pretty_name, module.executable_location] {
+        if !s.is_null() {
+            drop(CString::from_raw(s));
+        }
+    }
+}
+
 pub trait Sapi {
     fn name(&self) -> &[u8];
     fn pretty_name(&self) -> &[u8];
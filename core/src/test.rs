@@ -1,20 +1,38 @@
-use std::ffi::CString;
-use std::mem::MaybeUninit;
-
-use rusty_php_sys::zend::execute::zend_eval_string_ex;
-use rusty_php_sys::zend::Zval;
+use std::cell::{Cell, RefCell};
+use std::panic::{catch_unwind, resume_unwind, AssertUnwindSafe};
+use std::sync::{Mutex, MutexGuard, PoisonError};
 
 use crate::callback::{Callback, SapiCallback};
+use crate::diagnostic::Diagnostic;
 use crate::sapi::Sapi;
-use crate::{PhpInit, PhpRequest};
+use crate::zend::zval::OwnedZval;
+use crate::{Err, Error, Ok, PhpInit, PhpRequest, Result};
+
+thread_local! {
+    /// Diagnostics raised on this thread, until taken by the test.
+    static DIAGNOSTICS: RefCell<Vec<Diagnostic>> = const { RefCell::new(Vec::new()) };
+}
+
+/// Held by whichever test runs PHP, which only runs once at a time.
+static ENGINE: Mutex<()> = Mutex::new(());
+
+/// Waits for other tests to shut PHP down, for tests starting it themselves
+/// rather than through a [`TestBed`]. PHP is theirs until the guard drops.
+pub fn lock_engine() -> MutexGuard<'static, ()> {
+    ENGINE.lock().unwrap_or_else(PoisonError::into_inner)
+}
 
 struct SapiCallbackImpl;
 
-impl SapiCallback for SapiCallbackImpl {}
+impl SapiCallback for SapiCallbackImpl {
+    fn on_diagnostic(&self, diagnostic: &Diagnostic) {
+        DIAGNOSTICS.with(|diagnostics| diagnostics.borrow_mut().push(diagnostic.clone()));
+    }
+}
 
-struct SapiImpl;
+pub struct TestSapi;
 
-impl Sapi for SapiImpl {
+impl Sapi for TestSapi {
     fn name(&self) -> &[u8] {
         b"rusty-php-testbed"
     }
@@ -34,12 +52,27 @@ impl Sapi for SapiImpl {
 
 pub struct TestBed {
     php: PhpRequest,
+    strict: Cell<bool>,
+    _engine: MutexGuard<'static, ()>,
 }
 
 impl TestBed {
     pub fn startup() -> Self {
+        Self::startup_with(|init| init)
+    }
+
+    /// Starts up with functions or classes registered by `init`.
+    pub fn startup_with<I>(init: I) -> Self
+    where
+        I: FnOnce(PhpInit<TestSapi>) -> PhpInit<TestSapi>,
+    {
+        let engine = lock_engine();
+        DIAGNOSTICS.with(|diagnostics| diagnostics.borrow_mut().clear());
+
         Self {
-            php: PhpInit::new(SapiImpl)
+            _engine: engine,
+            strict: Cell::new(false),
+            php: init(PhpInit::new(TestSapi))
                 .init()
                 .unwrap()
                 .startup_module()
@@ -53,28 +86,62 @@ impl TestBed {
         self.php.shutdown_all();
     }
 
-    pub fn eval(&self, contents: &str) -> Zval {
-        let mut retval = MaybeUninit::<Zval>::uninit();
+    pub fn request(&self) -> &PhpRequest {
+        &self.php
+    }
+
+    /// Evaluates an expression, panicking if it throws.
+    pub fn eval(&self, contents: &str) -> OwnedZval {
+        self.try_eval(contents).unwrap()
+    }
 
-        unsafe {
-            zend_eval_string_ex(
-                CString::from_vec_unchecked(contents.as_bytes().to_vec()).into_raw(),
-                retval.as_mut_ptr(),
-                CString::from_vec_unchecked(b"TestBed".to_vec()).into_raw(),
-                true,
-            );
+    /// Evaluates an expression. In strict mode, any diagnostic it raises
+    /// fails it as an `ErrorException`.
+    pub fn try_eval(&self, contents: &str) -> Result<OwnedZval> {
+        let raised = DIAGNOSTICS.with(|diagnostics| diagnostics.borrow().len());
+        let retval = self.php.eval_named(contents, "TestBed")?;
+
+        match self.strict.get() {
+            true => match DIAGNOSTICS.with(|diagnostics| diagnostics.borrow().get(raised).cloned())
+            {
+                Some(diagnostic) => Err(Error::Exception(diagnostic.into())),
+                _ => Ok(retval),
+            },
+            _ => Ok(retval),
         }
+    }
 
-        unsafe { retval.assume_init() }
+    /// Makes [`try_eval`](Self::try_eval) fail on warnings, notices and
+    /// deprecations.
+    pub fn set_strict(&self, strict: bool) {
+        self.strict.set(strict);
+    }
+
+    /// Takes the diagnostics raised since startup or the last call.
+    pub fn take_diagnostics(&self) -> Vec<Diagnostic> {
+        DIAGNOSTICS.with(|diagnostics| diagnostics.take())
     }
 
     pub fn run<F, R>(f: F) -> R
     where
         F: FnOnce(&TestBed) -> R,
     {
-        let bed = Self::startup();
-        let ret = f(&bed);
+        Self::run_with(|init| init, f)
+    }
+
+    pub fn run_with<I, F, R>(init: I, f: F) -> R
+    where
+        I: FnOnce(PhpInit<TestSapi>) -> PhpInit<TestSapi>,
+        F: FnOnce(&TestBed) -> R,
+    {
+        let bed = Self::startup_with(init);
+        // Shut down even if the test fails, so the next one can start.
+        let ret = catch_unwind(AssertUnwindSafe(|| f(&bed)));
         bed.shutdown();
-        ret
+
+        match ret {
+            std::result::Result::Ok(ret) => ret,
+            std::result::Result::Err(payload) => resume_unwind(payload),
+        }
     }
 }
@@ -1,51 +1,120 @@
-use rusty_php_sys::zend::{ZendArray, ZendBucket, Zval, HASH_FLAG_PACKED};
+use std::ffi::c_char;
 
+use rusty_php_sys::zend::hash::{
+    zend_hash_clean, zend_hash_index_del, zend_hash_index_find, zend_hash_index_update,
+    zend_hash_next_index_insert, zend_symtable_str_del, zend_symtable_str_find,
+    zend_symtable_str_update,
+};
+use rusty_php_sys::zend::{ZendArray, ZendBucket, Zval, HASH_FLAG_PACKED, IS_UNDEF, Z_TYPE_MASK};
+
+use crate::zend::convert::{FromZval, FromZvalError, IntoZval};
 use crate::zend::string::ZStr;
-use crate::zend::Value;
+use crate::zend::zval::OwnedZval;
+use crate::zend::{UnsupportedTypeError, Value};
 
-#[derive(Copy, Clone, Debug)]
-enum ZArrayElementRaw<'a> {
-    Packed(&'a Zval),
-    Normal(&'a ZendBucket),
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum ArrayKey<'a> {
+    Long(i64),
+    String(ZStr<'a>),
+}
+
+/// Types that can be used to address an element of a PHP array.
+pub trait AsArrayKey {
+    fn as_array_key(&self) -> ArrayKey<'_>;
+}
+
+macro_rules! impl_as_array_key_for_integer {
+    ($($ty: ty),*) => {
+        $(
+            impl AsArrayKey for $ty {
+                #[allow(clippy::unnecessary_cast)]
+                fn as_array_key(&self) -> ArrayKey<'_> {
+                    ArrayKey::Long(*self as i64)
+                }
+            }
+        )*
+    };
+}
+
+impl_as_array_key_for_integer!(i8, i16, i32, i64, isize, u8, u16, u32);
+
+impl AsArrayKey for str {
+    fn as_array_key(&self) -> ArrayKey<'_> {
+        ArrayKey::String(self.into())
+    }
+}
+
+impl AsArrayKey for String {
+    fn as_array_key(&self) -> ArrayKey<'_> {
+        ArrayKey::String(self.as_str().into())
+    }
+}
+
+impl AsArrayKey for [u8] {
+    fn as_array_key(&self) -> ArrayKey<'_> {
+        ArrayKey::String(self.into())
+    }
+}
+
+impl AsArrayKey for Vec<u8> {
+    fn as_array_key(&self) -> ArrayKey<'_> {
+        ArrayKey::String(self.as_slice().into())
+    }
+}
+
+impl<'a> AsArrayKey for ArrayKey<'a> {
+    fn as_array_key(&self) -> ArrayKey<'_> {
+        *self
+    }
+}
+
+impl<T> AsArrayKey for &T
+where
+    T: AsArrayKey + ?Sized,
+{
+    fn as_array_key(&self) -> ArrayKey<'_> {
+        (**self).as_array_key()
+    }
 }
 
 #[derive(Debug)]
 pub struct ZArrayElement<'a> {
-    raw: ZArrayElementRaw<'a>,
+    key: ArrayKey<'a>,
+    value: &'a Zval,
 }
 
 impl<'a> ZArrayElement<'a> {
-    pub fn key(&self) -> Option<ZStr<'a>> {
-        match self.raw {
-            ZArrayElementRaw::Packed(_) => None,
-            ZArrayElementRaw::Normal(b) => Some(unsafe { &*b.key }.into()),
-        }
+    pub fn key(&self) -> ArrayKey<'a> {
+        self.key
     }
 
-    pub fn value(&self) -> Value<'a> {
-        match self.raw {
-            ZArrayElementRaw::Packed(v) => v.into(),
-            ZArrayElementRaw::Normal(b) => (&b.val).into(),
-        }
+    pub fn value(&self) -> Result<Value<'a>, UnsupportedTypeError> {
+        self.value.try_into()
     }
 }
 
 impl<'a> From<&'a ZendBucket> for ZArrayElement<'a> {
     fn from(value: &'a ZendBucket) -> Self {
+        let key = match unsafe { value.key.as_ref() } {
+            Some(k) => ArrayKey::String(k.into()),
+            _ => ArrayKey::Long(value.h as i64),
+        };
+
         Self {
-            raw: ZArrayElementRaw::Normal(value),
+            key,
+            value: &value.val,
         }
     }
 }
 
-impl<'a> From<&'a Zval> for ZArrayElement<'a> {
-    fn from(value: &'a Zval) -> Self {
-        Self {
-            raw: ZArrayElementRaw::Packed(value),
-        }
-    }
+fn is_undef(zval: &Zval) -> bool {
+    unsafe { zval.type_info.type_info & Z_TYPE_MASK == IS_UNDEF }
 }
 
+/// Iterates over the elements of an array in insertion order.
+///
+/// Unset elements leave `IS_UNDEF` tombstones behind until the table is
+/// rehashed, so every used slot is visited and the tombstones are skipped.
 pub struct ZArrayIter<'a> {
     inner: ZArray<'a>,
     cursor: usize,
@@ -55,21 +124,29 @@ impl<'a> Iterator for ZArrayIter<'a> {
     type Item = ZArrayElement<'a>;
 
     fn next(&mut self) -> Option<Self::Item> {
-        if self.cursor >= self.inner.len() {
-            return None;
-        }
+        while self.cursor < self.inner.raw.n_num_used as usize {
+            let position = self.cursor;
+            self.cursor += 1;
 
-        let value = match self.inner.is_packed() {
-            true => (&self.inner.as_raw_slice_packed()[self.cursor]).into(),
-            _ => (&self.inner.as_raw_slice()[self.cursor]).into(),
-        };
+            // Packed arrays store bare zvals, the key being the position.
+            let element = match self.inner.is_packed() {
+                true => ZArrayElement {
+                    key: ArrayKey::Long(position as i64),
+                    value: &self.inner.as_raw_slice_packed()[position],
+                },
+                _ => (&self.inner.as_raw_slice()[position]).into(),
+            };
 
-        self.cursor += 1;
-        Some(value)
+            if !is_undef(element.value) {
+                return Some(element);
+            }
+        }
+
+        None
     }
 }
 
-#[derive(Debug)]
+#[derive(Copy, Clone, Debug)]
 pub struct ZArray<'a> {
     pub raw: &'a ZendArray,
 }
@@ -83,25 +160,67 @@ impl<'a> ZArray<'a> {
         self.len() == 0
     }
 
+    pub fn extract<T>(&self) -> Result<T, FromZvalError>
+    where
+        T: FromZval<'a>,
+    {
+        T::from_zval(&Value::Array(*self))
+    }
+
+    /// Looks up an element with PHP's key semantics, so `"5"` and `5` address
+    /// the same element.
+    pub fn get<K>(&self, key: K) -> Result<Option<Value<'a>>, UnsupportedTypeError>
+    where
+        K: AsArrayKey,
+    {
+        self.find(key.as_array_key())
+            .map(Value::try_from)
+            .transpose()
+    }
+
+    pub fn get_index(&self, index: i64) -> Result<Option<Value<'a>>, UnsupportedTypeError> {
+        self.get(index)
+    }
+
+    pub fn contains_key<K>(&self, key: K) -> bool
+    where
+        K: AsArrayKey,
+    {
+        self.find(key.as_array_key()).is_some()
+    }
+
+    fn find(&self, key: ArrayKey<'_>) -> Option<&'a Zval> {
+        let zval = unsafe {
+            match key {
+                ArrayKey::Long(h) => zend_hash_index_find(self.raw, h as _),
+                ArrayKey::String(s) => zend_symtable_str_find(
+                    self.raw,
+                    s.as_bytes().as_ptr() as *const c_char,
+                    s.len(),
+                ),
+            }
+        };
+
+        unsafe { zval.as_ref() }
+    }
+
     pub fn is_packed(&self) -> bool {
         self.raw.flags & HASH_FLAG_PACKED != 0
     }
 
+    /// Returns the used buckets of a hash array, including the `IS_UNDEF`
+    /// slots of unset elements.
     pub fn as_raw_slice(&self) -> &'a [ZendBucket] {
         unsafe {
-            std::slice::from_raw_parts(
-                self.raw.array_data.ar_data,
-                self.raw.n_num_of_elements as usize,
-            )
+            std::slice::from_raw_parts(self.raw.array_data.ar_data, self.raw.n_num_used as usize)
         }
     }
 
+    /// Returns the used slots of a packed array, including the `IS_UNDEF`
+    /// slots of unset elements.
     pub fn as_raw_slice_packed(&self) -> &'a [Zval] {
         unsafe {
-            std::slice::from_raw_parts(
-                self.raw.array_data.ar_packed,
-                self.raw.n_num_of_elements as usize,
-            )
+            std::slice::from_raw_parts(self.raw.array_data.ar_packed, self.raw.n_num_used as usize)
         }
     }
 }
@@ -129,3 +248,118 @@ impl<'a> IntoIterator for ZArray<'a> {
         }
     }
 }
+
+/// A PHP array that can be modified in place.
+///
+/// Obtained from [`OwnedZval::array_mut`], which separates shared arrays
+/// first, so changes are never visible through other references.
+pub struct ZArrayMut<'a> {
+    raw: &'a mut ZendArray,
+}
+
+impl<'a> ZArrayMut<'a> {
+    /// # Safety
+    /// The array must be valid and not shared (its refcount must be 1) for
+    /// the whole lifetime `'a`.
+    pub unsafe fn from_raw(raw: *mut ZendArray) -> Self {
+        Self { raw: &mut *raw }
+    }
+
+    pub fn as_array(&self) -> ZArray<'_> {
+        ZArray::from(&*self.raw)
+    }
+
+    pub fn len(&self) -> usize {
+        self.as_array().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.as_array().is_empty()
+    }
+
+    pub fn get<K>(&self, key: K) -> Result<Option<Value<'_>>, UnsupportedTypeError>
+    where
+        K: AsArrayKey,
+    {
+        self.as_array().get(key)
+    }
+
+    pub fn get_index(&self, index: i64) -> Result<Option<Value<'_>>, UnsupportedTypeError> {
+        self.as_array().get_index(index)
+    }
+
+    pub fn contains_key<K>(&self, key: K) -> bool
+    where
+        K: AsArrayKey,
+    {
+        self.as_array().contains_key(key)
+    }
+
+    /// Inserts or replaces an element. Numeric string keys are stored as
+    /// integers, as they are in PHP.
+    pub fn insert<K, V>(&mut self, key: K, value: V)
+    where
+        K: AsArrayKey,
+        V: IntoZval,
+    {
+        let mut raw = value.into_zval().into_raw();
+
+        unsafe {
+            match key.as_array_key() {
+                ArrayKey::Long(h) => zend_hash_index_update(self.raw, h as _, &mut raw),
+                ArrayKey::String(s) => zend_symtable_str_update(
+                    self.raw,
+                    s.as_bytes().as_ptr() as *const c_char,
+                    s.len(),
+                    &mut raw,
+                ),
+            }
+        };
+    }
+
+    /// Appends an element at the next free integer key, like `$array[] = $value`.
+    ///
+    /// Returns `false` if the next key would overflow, in which case the value
+    /// is dropped.
+    pub fn push<V>(&mut self, value: V) -> bool
+    where
+        V: IntoZval,
+    {
+        let mut value = value.into_zval();
+
+        match unsafe { zend_hash_next_index_insert(self.raw, value.as_mut_ptr()) }.is_null() {
+            true => false,
+            _ => {
+                value.into_raw();
+                true
+            }
+        }
+    }
+
+    /// Removes an element, returning it if it was present.
+    pub fn remove<K>(&mut self, key: K) -> Option<OwnedZval>
+    where
+        K: AsArrayKey,
+    {
+        let key = key.as_array_key();
+        let found = ZArray::from(&*self.raw).find(key)?;
+
+        // Take a reference of our own before the table releases its one.
+        let value = unsafe { OwnedZval::copy_from(found) };
+
+        unsafe {
+            match key {
+                ArrayKey::Long(h) => zend_hash_index_del(self.raw, h as _),
+                ArrayKey::String(s) => {
+                    zend_symtable_str_del(self.raw, s.as_bytes().as_ptr() as *const c_char, s.len())
+                }
+            }
+        };
+
+        Some(value)
+    }
+
+    pub fn clear(&mut self) {
+        unsafe { zend_hash_clean(self.raw) };
+    }
+}
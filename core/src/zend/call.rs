@@ -0,0 +1,164 @@
+//! Calls from Rust into PHP code.
+
+use std::cell::Cell;
+use std::mem::{self, MaybeUninit};
+use std::os::unix::ffi::OsStrExt;
+use std::path::Path;
+use std::ptr::null_mut;
+
+use rusty_php_sys::zend::api::{zend_call_function, ZendFcallInfo, ZendFcallInfoCache};
+use rusty_php_sys::zend::bailout::zend_try;
+use rusty_php_sys::zend::compile::{ZendExecuteData, ZendInternalFunction, ZEND_USER_FUNCTION};
+use rusty_php_sys::zend::exceptions::zend_is_unwind_exit;
+use rusty_php_sys::zend::execute::zend_eval_string_ex;
+use rusty_php_sys::zend::globals::eg;
+use rusty_php_sys::zend::observer::ZendObserverFcallHandlers;
+use rusty_php_sys::zend::stream::{zend_destroy_file_handle, zend_stream_init_filename};
+use rusty_php_sys::zend::{ZendResult, Zval};
+
+use crate::error::{clear_last_error, Error, Stage};
+use crate::sapi::create_cstring;
+use crate::sys::php_execute_script;
+use crate::zend::convert::{IntoArgs, IntoZval};
+use crate::zend::exception::PhpException;
+use crate::zend::zval::OwnedZval;
+use crate::{Err, Ok, Result};
+
+thread_local! {
+    /// Whether the script executed on this thread was ended by `exit()`.
+    static EXITED: Cell<bool> = const { Cell::new(false) };
+}
+
+/// Observes the top-level code of every file, as `exit()` unwinds out of it
+/// with an exception which the engine drops once the script is over.
+pub(crate) unsafe extern "C" fn observe_fcall(
+    execute_data: *mut ZendExecuteData,
+) -> ZendObserverFcallHandlers {
+    // Every function starts with the common fields of an internal function.
+    let func = (*execute_data).func as *const ZendInternalFunction;
+    match (*func).ty == ZEND_USER_FUNCTION && (*func).function_name.is_null() {
+        true => ZendObserverFcallHandlers {
+            begin: None,
+            end: Some(on_code_end),
+        },
+        _ => ZendObserverFcallHandlers::default(),
+    }
+}
+
+unsafe extern "C" fn on_code_end(_execute_data: *mut ZendExecuteData, _retval: *mut Zval) {
+    let exception = eg!(exception);
+    if !exception.is_null() && zend_is_unwind_exit(exception) {
+        EXITED.with(|exited| exited.set(true));
+    }
+}
+
+/// Runs `f`, which calls into the engine, and fails with the exception it
+/// left behind, if any. The exception is cleared so the request stays usable.
+/// A bailout out of `f` fails with [`Error::Bailout`] for `stage`.
+///
+/// Outside of any PHP frame the engine turns an uncaught exception into a
+/// fatal error, so an empty frame is pushed for the duration of the call.
+pub(crate) fn in_frame<F, R>(stage: Stage, f: F) -> Result<R>
+where
+    F: FnOnce() -> R,
+{
+    let ret = unsafe {
+        let prev = eg!(current_execute_data);
+        let mut frame: ZendExecuteData = mem::zeroed();
+
+        if prev.is_null() {
+            eg!(current_execute_data) = &mut frame;
+        }
+
+        let ret = zend_try(f);
+        eg!(current_execute_data) = prev;
+        ret
+    };
+
+    match (ret, PhpException::take()) {
+        (None, _) => Err(Error::bailout(stage)),
+        (_, Some(exception)) => Err(Error::Exception(exception)),
+        (Some(ret), _) => Ok(ret),
+    }
+}
+
+/// Calls a PHP callable: a function name, a `Closure` or any other invokable
+/// object, or a `[$object, 'method']` / `['Class', 'method']` array.
+pub fn call<F, A>(callable: F, args: A) -> Result<OwnedZval>
+where
+    F: IntoZval,
+    A: IntoArgs,
+{
+    let callable = callable.into_zval();
+    let mut args = args.into_args();
+    // Resolved by `zend_call_function`, which throws for invalid callables.
+    let mut fcc: ZendFcallInfoCache = unsafe { mem::zeroed() };
+
+    let mut retval = OwnedZval::undef();
+    let mut fci = ZendFcallInfo {
+        size: mem::size_of::<ZendFcallInfo>(),
+        // A borrowed copy: the callable stays owned by `callable`.
+        function_name: unsafe { std::ptr::read(callable.as_raw()) },
+        retval: retval.as_mut_ptr(),
+        params: args.as_mut_ptr() as *mut Zval,
+        object: null_mut(),
+        param_count: args.len() as u32,
+        named_params: null_mut(),
+    };
+
+    match in_frame(Stage::Call, || unsafe {
+        zend_call_function(&mut fci, &mut fcc)
+    })? {
+        ZendResult::Success => Ok(retval),
+        _ => Err(Error::stage(Stage::Call)),
+    }
+}
+
+/// Evaluates PHP code as the body of `return <code>;`, naming it `name` in
+/// error messages and traces.
+pub fn eval(code: &str, name: &str) -> Result<OwnedZval> {
+    let code = create_cstring(code.as_bytes());
+    let name = create_cstring(name.as_bytes());
+    let mut retval = OwnedZval::undef();
+
+    let result = in_frame(Stage::Eval, || unsafe {
+        zend_eval_string_ex(code.as_ptr(), retval.as_mut_ptr(), name.as_ptr(), false)
+    })?;
+
+    match result {
+        ZendResult::Success => Ok(retval),
+        _ => Err(Error::stage(Stage::Eval)),
+    }
+}
+
+/// Executes the script at `path` as the primary script of the request, with
+/// its output sent to the SAPI. A script ending in `exit()` succeeds; one
+/// ending in a fatal error, such as an uncaught exception, fails with
+/// [`Error::Stage`] explained by that error.
+pub fn execute<P>(path: P) -> Result<()>
+where
+    P: AsRef<Path>,
+{
+    let path = create_cstring(path.as_ref().as_os_str().as_bytes());
+    let mut file_handle = MaybeUninit::uninit();
+    clear_last_error();
+    EXITED.with(|exited| exited.set(false));
+
+    let executed = in_frame(Stage::Execute, || unsafe {
+        // The handle holds its own copy of the file name.
+        zend_stream_init_filename(file_handle.as_mut_ptr(), path.as_ptr());
+        let file_handle = file_handle.assume_init_mut();
+        file_handle.primary_script = true;
+
+        let executed = php_execute_script(file_handle);
+        zend_destroy_file_handle(file_handle);
+        executed
+    })?;
+
+    // The engine reports uncaught exceptions itself, and fails the script
+    // for `exit()` too.
+    match executed || EXITED.with(|exited| exited.replace(false)) {
+        true => Ok(()),
+        _ => Err(Error::stage(Stage::Execute)),
+    }
+}
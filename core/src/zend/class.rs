@@ -0,0 +1,515 @@
+//! PHP classes defined in Rust.
+
+use std::any::Any;
+use std::cell::RefCell;
+use std::collections::BTreeMap;
+use std::ffi::{c_int, CStr};
+use std::marker::PhantomData;
+use std::mem::{offset_of, size_of, zeroed};
+use std::panic::{catch_unwind, AssertUnwindSafe};
+use std::ptr::{self, addr_of_mut, null_mut};
+use std::sync::{Arc, OnceLock, PoisonError, RwLock};
+
+use rusty_php_sys::zend::alloc::emalloc;
+use rusty_php_sys::zend::api::{
+    object_properties_init, zend_class_implements, zend_declare_class_constant_ex,
+    zend_declare_property_ex, zend_lookup_class_ex, zend_register_internal_class_ex,
+    ZEND_FETCH_CLASS_NO_AUTOLOAD,
+};
+use rusty_php_sys::zend::compile::{
+    ZEND_ACC_PRIVATE, ZEND_ACC_PROTECTED, ZEND_ACC_PUBLIC, ZEND_ACC_STATIC,
+};
+use rusty_php_sys::zend::exceptions::zend_throw_error;
+use rusty_php_sys::zend::object_handlers::{std_object_handlers, ZendObjectHandlers};
+use rusty_php_sys::zend::objects::{
+    zend_object_properties_size, zend_object_std_dtor, zend_object_std_init,
+};
+use rusty_php_sys::zend::{ZendClassEntry, ZendObject};
+
+use crate::zend::convert::IntoZval;
+use crate::zend::function::{Arg, Arguments, Function, FunctionTable};
+use crate::zend::string::{ZStr, ZString};
+use crate::zend::zval::OwnedZval;
+use crate::{Err, Error, Ok, Result};
+
+type Factory = Arc<dyn Fn() -> Box<dyn Any> + Send + Sync>;
+
+/// Constructors of the Rust state of registered classes, by class name.
+static CLASSES: RwLock<BTreeMap<Vec<u8>, Factory>> = RwLock::new(BTreeMap::new());
+
+/// Forgets the registered classes, once PHP is shut down.
+pub(crate) fn unregister_classes() {
+    CLASSES
+        .write()
+        .unwrap_or_else(PoisonError::into_inner)
+        .clear();
+}
+
+static HANDLERS: OnceLock<ZendObjectHandlers> = OnceLock::new();
+
+/// An object of a registered class: the Rust state followed by the
+/// `zend_object`, whose property slots trail the allocation. Methods can call
+/// back into PHP and re-enter each other, so the state is only ever borrowed
+/// through its `RefCell`.
+#[repr(C)]
+struct ObjectStorage {
+    state: Option<RefCell<Box<dyn Any>>>,
+    std: ZendObject,
+}
+
+fn handlers() -> &'static ZendObjectHandlers {
+    HANDLERS.get_or_init(|| {
+        let mut handlers = unsafe { std_object_handlers };
+        handlers.offset = offset_of!(ObjectStorage, std) as c_int;
+        handlers.free_obj = Some(free_object);
+        handlers.clone_obj = None;
+        handlers
+    })
+}
+
+unsafe fn storage(object: *mut ZendObject) -> *mut ObjectStorage {
+    (object as *mut u8).sub(offset_of!(ObjectStorage, std)) as *mut ObjectStorage
+}
+
+/// The Rust state of an object, if it is an instance of a registered class.
+/// The object must outlive `'a`.
+pub(crate) unsafe fn object_state<'a>(
+    object: *mut ZendObject,
+) -> Option<&'a RefCell<Box<dyn Any>>> {
+    match ptr::eq((*object).handlers, handlers()) {
+        true => (*storage(object)).state.as_ref(),
+        _ => None,
+    }
+}
+
+/// Finds the state constructor of a class, or of the closest registered
+/// parent for classes extending a registered class in PHP.
+unsafe fn factory(mut ce: *const ZendClassEntry) -> Option<Factory> {
+    let classes = CLASSES.read().unwrap_or_else(PoisonError::into_inner);
+    while !ce.is_null() {
+        if let Some(factory) = classes.get(ZStr::from(&*(*ce).name).as_bytes()) {
+            return Some(Arc::clone(factory));
+        }
+        ce = (*ce).parent.parent;
+    }
+    None
+}
+
+unsafe extern "C" fn create_object(ce: *mut ZendClassEntry) -> *mut ZendObject {
+    let state =
+        factory(ce).and_then(
+            |factory| match catch_unwind(AssertUnwindSafe(|| factory())) {
+                std::result::Result::Ok(state) => Some(state),
+                std::result::Result::Err(_) => {
+                    tracing::error!("Creating the state of a PHP object panicked");
+                    None
+                }
+            },
+        );
+
+    let storage =
+        emalloc(size_of::<ObjectStorage>() + zend_object_properties_size(ce)) as *mut ObjectStorage;
+    ptr::write(addr_of_mut!((*storage).state), state.map(RefCell::new));
+
+    let object = addr_of_mut!((*storage).std);
+    zend_object_std_init(object, ce);
+    object_properties_init(object, ce);
+    (*object).handlers = handlers();
+    object
+}
+
+unsafe extern "C" fn free_object(object: *mut ZendObject) {
+    let state = (*storage(object)).state.take();
+    if catch_unwind(AssertUnwindSafe(|| drop(state))).is_err() {
+        tracing::error!("Dropping the state of a PHP object panicked");
+    }
+
+    zend_object_std_dtor(object);
+}
+
+unsafe fn lookup_class(name: &str) -> Option<*mut ZendClassEntry> {
+    let name = ZString::new(name);
+    let ce = zend_lookup_class_ex(name.as_ptr(), null_mut(), ZEND_FETCH_CLASS_NO_AUTOLOAD);
+    (!ce.is_null()).then_some(ce)
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Visibility {
+    Public,
+    Protected,
+    Private,
+}
+
+impl Visibility {
+    fn flags(self) -> u32 {
+        match self {
+            Visibility::Public => ZEND_ACC_PUBLIC,
+            Visibility::Protected => ZEND_ACC_PROTECTED,
+            Visibility::Private => ZEND_ACC_PRIVATE,
+        }
+    }
+}
+
+/// The value of a class constant or the default of a property. Internal
+/// classes outlive requests, so only scalars are allowed.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Scalar {
+    Null,
+    Bool(bool),
+    Long(i64),
+    Double(f64),
+    String(String),
+}
+
+impl Scalar {
+    fn into_permanent_zval(self) -> OwnedZval {
+        match self {
+            Scalar::Null => OwnedZval::null(),
+            Scalar::Bool(v) => OwnedZval::bool(v),
+            Scalar::Long(v) => OwnedZval::long(v),
+            Scalar::Double(v) => OwnedZval::double(v),
+            Scalar::String(v) => ZString::new_permanent(v).into_zval(),
+        }
+    }
+}
+
+impl From<()> for Scalar {
+    fn from(_: ()) -> Self {
+        Scalar::Null
+    }
+}
+
+impl From<bool> for Scalar {
+    fn from(value: bool) -> Self {
+        Scalar::Bool(value)
+    }
+}
+
+impl From<i64> for Scalar {
+    fn from(value: i64) -> Self {
+        Scalar::Long(value)
+    }
+}
+
+impl From<f64> for Scalar {
+    fn from(value: f64) -> Self {
+        Scalar::Double(value)
+    }
+}
+
+impl From<&str> for Scalar {
+    fn from(value: &str) -> Self {
+        Scalar::String(value.to_string())
+    }
+}
+
+impl From<String> for Scalar {
+    fn from(value: String) -> Self {
+        Scalar::String(value)
+    }
+}
+
+/// A method of a class whose objects carry a `T`.
+pub struct Method<T> {
+    function: Function,
+    _state: PhantomData<fn(&mut T)>,
+}
+
+impl<T> Method<T>
+where
+    T: Any,
+{
+    /// An instance method, called with the state of the object. The state is
+    /// borrowed for the whole call, so calling a method of the same object
+    /// from the handler throws an `Error`.
+    pub fn new<F, R>(name: &str, handler: F) -> Self
+    where
+        F: Fn(&mut T, &Arguments<'_>) -> Result<R> + Send + Sync + 'static,
+        R: IntoZval,
+    {
+        Self::instance(Function::new(name, move |args| {
+            let Some(mut state) = this_state(args)?.try_borrow_mut().ok() else {
+                return throw_error(c"Object is already in use by a running method");
+            };
+            match state.downcast_mut() {
+                Some(state) => handler(state, args),
+                _ => throw_error(NOT_BACKED),
+            }
+        }))
+    }
+
+    /// An instance method which only reads the state of the object, and can
+    /// run alongside other such methods of the same object.
+    pub fn new_ref<F, R>(name: &str, handler: F) -> Self
+    where
+        F: Fn(&T, &Arguments<'_>) -> Result<R> + Send + Sync + 'static,
+        R: IntoZval,
+    {
+        Self::instance(Function::new(name, move |args| {
+            let Some(state) = this_state(args)?.try_borrow().ok() else {
+                return throw_error(c"Object is being changed by a running method");
+            };
+            match state.downcast_ref() {
+                Some(state) => handler(state, args),
+                _ => throw_error(NOT_BACKED),
+            }
+        }))
+    }
+
+    fn instance(mut function: Function) -> Self {
+        function.flags = ZEND_ACC_PUBLIC;
+
+        Self {
+            function,
+            _state: PhantomData,
+        }
+    }
+
+    pub fn new_static<F, R>(name: &str, handler: F) -> Self
+    where
+        F: Fn(&Arguments<'_>) -> Result<R> + Send + Sync + 'static,
+        R: IntoZval,
+    {
+        let mut function = Function::new(name, handler);
+        function.flags = ZEND_ACC_PUBLIC | ZEND_ACC_STATIC;
+
+        Self {
+            function,
+            _state: PhantomData,
+        }
+    }
+
+    pub fn arg(mut self, arg: Arg) -> Self {
+        self.function = self.function.arg(arg);
+        self
+    }
+
+    pub fn visibility(mut self, visibility: Visibility) -> Self {
+        self.function.flags = (self.function.flags & ZEND_ACC_STATIC) | visibility.flags();
+        self
+    }
+}
+
+const NOT_BACKED: &CStr = c"Object is not backed by the expected Rust state";
+
+/// The state of the object a method was called on.
+fn this_state<'a>(args: &Arguments<'a>) -> Result<&'a RefCell<Box<dyn Any>>> {
+    match args
+        .this()
+        .and_then(|this| unsafe { object_state(this.as_mut_ptr()) })
+    {
+        Some(state) => Ok(state),
+        _ => throw_error(NOT_BACKED),
+    }
+}
+
+fn throw_error<R>(message: &CStr) -> Result<R> {
+    unsafe { zend_throw_error(null_mut(), message.as_ptr()) };
+    Err(Error::Thrown)
+}
+
+struct Property {
+    name: String,
+    default: Scalar,
+    flags: u32,
+}
+
+/// A PHP class whose objects carry a Rust `T`, created with `T::default()`
+/// and dropped when the object is freed. Register it with
+/// [`PhpInit::class`](crate::PhpInit::class).
+pub struct Class<T> {
+    def: ClassDef,
+    _state: PhantomData<fn() -> T>,
+}
+
+impl<T> Class<T>
+where
+    T: Any + Default,
+{
+    pub fn new(name: &str) -> Self {
+        Self {
+            def: ClassDef {
+                name: name.to_string(),
+                parent: None,
+                interfaces: Vec::new(),
+                constants: Vec::new(),
+                properties: Vec::new(),
+                methods: Vec::new(),
+                factory: Arc::new(|| Box::<T>::default()),
+            },
+            _state: PhantomData,
+        }
+    }
+
+    /// Sets the parent class, which must already be registered by PHP or by
+    /// an earlier class.
+    pub fn extends(mut self, parent: &str) -> Self {
+        self.def.parent = Some(parent.to_string());
+        self
+    }
+
+    pub fn implements(mut self, interface: &str) -> Self {
+        self.def.interfaces.push(interface.to_string());
+        self
+    }
+
+    pub fn constant<V>(mut self, name: &str, value: V) -> Self
+    where
+        V: Into<Scalar>,
+    {
+        self.def.constants.push((name.to_string(), value.into()));
+        self
+    }
+
+    pub fn property<V>(mut self, name: &str, default: V, visibility: Visibility) -> Self
+    where
+        V: Into<Scalar>,
+    {
+        self.def.properties.push(Property {
+            name: name.to_string(),
+            default: default.into(),
+            flags: visibility.flags(),
+        });
+        self
+    }
+
+    pub fn static_property<V>(mut self, name: &str, default: V, visibility: Visibility) -> Self
+    where
+        V: Into<Scalar>,
+    {
+        self.def.properties.push(Property {
+            name: name.to_string(),
+            default: default.into(),
+            flags: visibility.flags() | ZEND_ACC_STATIC,
+        });
+        self
+    }
+
+    pub fn method(mut self, method: Method<T>) -> Self {
+        self.def.methods.push(method.function);
+        self
+    }
+}
+
+impl<T> From<Class<T>> for ClassDef {
+    fn from(class: Class<T>) -> Self {
+        class.def
+    }
+}
+
+/// A class with the type of its state erased.
+pub(crate) struct ClassDef {
+    name: String,
+    parent: Option<String>,
+    interfaces: Vec<String>,
+    constants: Vec<(String, Scalar)>,
+    properties: Vec<Property>,
+    methods: Vec<Function>,
+    factory: Factory,
+}
+
+struct PreparedClass {
+    def: ClassDef,
+    methods: FunctionTable,
+}
+
+impl PreparedClass {
+    unsafe fn register(&self) -> Result<()> {
+        let def = &self.def;
+
+        let parent = match &def.parent {
+            Some(parent) => match lookup_class(parent) {
+                Some(ce) => ce,
+                _ => {
+                    return Err(Error::custom(format!(
+                        "Parent class {} of {} is not registered",
+                        parent, def.name
+                    )))
+                }
+            },
+            _ => null_mut(),
+        };
+
+        let mut class_entry: ZendClassEntry = zeroed();
+        class_entry.name = ZString::new_permanent(&def.name).into_raw();
+        class_entry.info.internal.builtin_functions = self.methods.as_ptr();
+
+        let ce = zend_register_internal_class_ex(&mut class_entry, parent);
+        if ce.is_null() {
+            return Err(Error::custom(format!(
+                "Registering class {} failed",
+                def.name
+            )));
+        }
+        (*ce).create_object.create_object = Some(create_object);
+
+        for interface in &def.interfaces {
+            match lookup_class(interface) {
+                Some(iface) => zend_class_implements(ce, 1, iface),
+                _ => {
+                    return Err(Error::custom(format!(
+                        "Interface {} of {} is not registered",
+                        interface, def.name
+                    )))
+                }
+            }
+        }
+
+        for (name, value) in &def.constants {
+            let mut value = value.clone().into_permanent_zval().into_raw();
+            zend_declare_class_constant_ex(
+                ce,
+                ZString::new_permanent(name).into_raw(),
+                &mut value,
+                ZEND_ACC_PUBLIC as c_int,
+                null_mut(),
+            );
+        }
+
+        for property in &def.properties {
+            let mut value = property.default.clone().into_permanent_zval().into_raw();
+            zend_declare_property_ex(
+                ce,
+                ZString::new_permanent(&property.name).into_raw(),
+                &mut value,
+                property.flags as c_int,
+                null_mut(),
+            );
+        }
+
+        CLASSES
+            .write()
+            .unwrap_or_else(PoisonError::into_inner)
+            .insert(def.name.as_bytes().to_vec(), Arc::clone(&def.factory));
+
+        Ok(())
+    }
+}
+
+/// The classes to register, with their methods, which the engine points into
+/// until it shuts down.
+pub(crate) struct ClassTable {
+    classes: Vec<PreparedClass>,
+}
+
+impl ClassTable {
+    pub(crate) fn new(classes: Vec<ClassDef>) -> Self {
+        let classes = classes
+            .into_iter()
+            .map(|mut def| PreparedClass {
+                methods: FunctionTable::new(Some(&def.name), std::mem::take(&mut def.methods)),
+                def,
+            })
+            .collect();
+
+        Self { classes }
+    }
+
+    /// Registers the classes in order, which can only be done from `MINIT`.
+    pub(crate) unsafe fn register(&self) -> Result<()> {
+        for class in &self.classes {
+            class.register()?;
+        }
+        Ok(())
+    }
+}
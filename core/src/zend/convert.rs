@@ -0,0 +1,586 @@
+//! Conversions between Rust types and zvals.
+
+use std::collections::{BTreeMap, HashMap};
+use std::error::Error;
+use std::fmt::{Display, Formatter};
+use std::hash::BuildHasher;
+
+use crate::zend::array::{ArrayKey, AsArrayKey, ZArray, ZArrayMut};
+use crate::zend::string::{ZStr, ZString};
+use crate::zend::zval::OwnedZval;
+use crate::zend::{UnsupportedTypeError, Value};
+
+/// Types that can be converted into a PHP value.
+///
+/// Strings and arrays are allocated with the Zend allocator, so conversions
+/// must happen while a request is active. Byte strings (`&[u8]`, `Vec<u8>`)
+/// become PHP strings, which is why `u8` has no implementation on its own.
+pub trait IntoZval {
+    fn into_zval(self) -> OwnedZval;
+}
+
+/// Argument lists for calls into PHP: tuples of [`IntoZval`] values, or a
+/// `Vec` or array of values of one type.
+pub trait IntoArgs {
+    fn into_args(self) -> Vec<OwnedZval>;
+}
+
+impl IntoArgs for () {
+    fn into_args(self) -> Vec<OwnedZval> {
+        Vec::new()
+    }
+}
+
+impl<T> IntoArgs for Vec<T>
+where
+    T: IntoZval,
+{
+    fn into_args(self) -> Vec<OwnedZval> {
+        self.into_iter().map(IntoZval::into_zval).collect()
+    }
+}
+
+impl<T, const N: usize> IntoArgs for [T; N]
+where
+    T: IntoZval,
+{
+    fn into_args(self) -> Vec<OwnedZval> {
+        self.into_iter().map(IntoZval::into_zval).collect()
+    }
+}
+
+macro_rules! impl_into_args_for_tuple {
+    ($($name: ident),+) => {
+        impl<$($name),+> IntoArgs for ($($name,)+)
+        where
+            $($name: IntoZval,)+
+        {
+            #[allow(non_snake_case)]
+            fn into_args(self) -> Vec<OwnedZval> {
+                let ($($name,)+) = self;
+                vec![$($name.into_zval()),+]
+            }
+        }
+    };
+}
+
+impl_into_args_for_tuple!(A);
+impl_into_args_for_tuple!(A, B);
+impl_into_args_for_tuple!(A, B, C);
+impl_into_args_for_tuple!(A, B, C, D);
+impl_into_args_for_tuple!(A, B, C, D, E);
+impl_into_args_for_tuple!(A, B, C, D, E, F);
+impl_into_args_for_tuple!(A, B, C, D, E, F, G);
+impl_into_args_for_tuple!(A, B, C, D, E, F, G, H);
+
+impl IntoZval for OwnedZval {
+    fn into_zval(self) -> OwnedZval {
+        self
+    }
+}
+
+impl IntoZval for () {
+    fn into_zval(self) -> OwnedZval {
+        OwnedZval::null()
+    }
+}
+
+impl IntoZval for bool {
+    fn into_zval(self) -> OwnedZval {
+        OwnedZval::bool(self)
+    }
+}
+
+macro_rules! impl_into_zval_for_integer {
+    ($($ty: ty),*) => {
+        $(
+            impl IntoZval for $ty {
+                #[allow(clippy::unnecessary_cast)]
+                fn into_zval(self) -> OwnedZval {
+                    OwnedZval::long(self as i64)
+                }
+            }
+        )*
+    };
+}
+
+impl_into_zval_for_integer!(i8, i16, i32, i64, isize, u16, u32);
+
+macro_rules! impl_into_zval_for_wide_integer {
+    ($($ty: ty),*) => {
+        $(
+            impl IntoZval for $ty {
+                /// Values beyond `PHP_INT_MAX` become floats, as they do in PHP.
+                fn into_zval(self) -> OwnedZval {
+                    match i64::try_from(self) {
+                        Ok(v) => OwnedZval::long(v),
+                        _ => OwnedZval::double(self as f64),
+                    }
+                }
+            }
+        )*
+    };
+}
+
+impl_into_zval_for_wide_integer!(u64, usize);
+
+impl IntoZval for f32 {
+    fn into_zval(self) -> OwnedZval {
+        OwnedZval::double(self as f64)
+    }
+}
+
+impl IntoZval for f64 {
+    fn into_zval(self) -> OwnedZval {
+        OwnedZval::double(self)
+    }
+}
+
+impl<T> IntoZval for Option<T>
+where
+    T: IntoZval,
+{
+    fn into_zval(self) -> OwnedZval {
+        match self {
+            Some(v) => v.into_zval(),
+            _ => OwnedZval::null(),
+        }
+    }
+}
+
+impl IntoZval for &[u8] {
+    fn into_zval(self) -> OwnedZval {
+        ZString::new(self).into_zval()
+    }
+}
+
+impl IntoZval for Vec<u8> {
+    fn into_zval(self) -> OwnedZval {
+        self.as_slice().into_zval()
+    }
+}
+
+impl IntoZval for &str {
+    fn into_zval(self) -> OwnedZval {
+        self.as_bytes().into_zval()
+    }
+}
+
+impl IntoZval for String {
+    fn into_zval(self) -> OwnedZval {
+        self.as_bytes().into_zval()
+    }
+}
+
+/// Gives write access to an array that was just created and is not shared.
+pub(crate) fn elements(array: &mut OwnedZval) -> ZArrayMut<'_> {
+    unsafe { ZArrayMut::from_raw((*array.as_mut_ptr()).value.arr) }
+}
+
+impl<T> IntoZval for Vec<T>
+where
+    T: IntoZval,
+{
+    fn into_zval(self) -> OwnedZval {
+        let mut array = OwnedZval::array_with_capacity(self.len());
+        let mut elements = elements(&mut array);
+
+        for value in self {
+            elements.push(value);
+        }
+
+        array
+    }
+}
+
+fn map_into_zval<K, V, I>(len: usize, iter: I) -> OwnedZval
+where
+    K: AsArrayKey,
+    V: IntoZval,
+    I: IntoIterator<Item = (K, V)>,
+{
+    let mut array = OwnedZval::array_with_capacity(len);
+    let mut elements = elements(&mut array);
+
+    for (key, value) in iter {
+        elements.insert(key, value);
+    }
+
+    array
+}
+
+impl<K, V, S> IntoZval for HashMap<K, V, S>
+where
+    K: AsArrayKey,
+    V: IntoZval,
+{
+    fn into_zval(self) -> OwnedZval {
+        map_into_zval(self.len(), self)
+    }
+}
+
+impl<K, V> IntoZval for BTreeMap<K, V>
+where
+    K: AsArrayKey,
+    V: IntoZval,
+{
+    fn into_zval(self) -> OwnedZval {
+        map_into_zval(self.len(), self)
+    }
+}
+
+macro_rules! impl_into_zval_for_tuple {
+    ($len: expr; $($name: ident),+) => {
+        impl<$($name),+> IntoZval for ($($name,)+)
+        where
+            $($name: IntoZval,)+
+        {
+            #[allow(non_snake_case)]
+            fn into_zval(self) -> OwnedZval {
+                let ($($name,)+) = self;
+                let mut array = OwnedZval::array_with_capacity($len);
+                let mut elements = elements(&mut array);
+
+                $(elements.push($name);)+
+
+                array
+            }
+        }
+    };
+}
+
+impl_into_zval_for_tuple!(1; A);
+impl_into_zval_for_tuple!(2; A, B);
+impl_into_zval_for_tuple!(3; A, B, C);
+impl_into_zval_for_tuple!(4; A, B, C, D);
+impl_into_zval_for_tuple!(5; A, B, C, D, E);
+impl_into_zval_for_tuple!(6; A, B, C, D, E, F);
+impl_into_zval_for_tuple!(7; A, B, C, D, E, F, G);
+impl_into_zval_for_tuple!(8; A, B, C, D, E, F, G, H);
+
+/// A step into a nested PHP array, used to locate an extraction failure.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum PathSegment {
+    Index(i64),
+    Key(String),
+}
+
+impl Display for PathSegment {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Index(i) => write!(f, "[{}]", i),
+            Self::Key(k) => write!(f, "[{:?}]", k),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum FromZvalErrorKind {
+    Mismatch {
+        expected: &'static str,
+        found: &'static str,
+    },
+    OutOfRange {
+        expected: &'static str,
+    },
+    InvalidUtf8,
+    Unsupported(UnsupportedTypeError),
+}
+
+impl Display for FromZvalErrorKind {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Mismatch { expected, found } => write!(f, "expected {}, got {}", expected, found),
+            Self::OutOfRange { expected } => write!(f, "value out of range for {}", expected),
+            Self::InvalidUtf8 => write!(f, "string is not valid UTF-8"),
+            Self::Unsupported(e) => e.fmt(f),
+        }
+    }
+}
+
+/// Failure to extract a Rust value, along with where in the PHP value it
+/// happened, e.g. `$[3]["name"]: expected string, got int`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct FromZvalError {
+    path: Vec<PathSegment>,
+    kind: FromZvalErrorKind,
+}
+
+impl FromZvalError {
+    pub fn new(kind: FromZvalErrorKind) -> Self {
+        Self { path: vec![], kind }
+    }
+
+    pub fn mismatch(expected: &'static str, found: &Value<'_>) -> Self {
+        Self::new(FromZvalErrorKind::Mismatch {
+            expected,
+            found: found.type_name(),
+        })
+    }
+
+    /// Marks the error as having happened inside the element at `segment`.
+    pub fn within(mut self, segment: PathSegment) -> Self {
+        self.path.insert(0, segment);
+        self
+    }
+
+    pub fn path(&self) -> &[PathSegment] {
+        &self.path
+    }
+
+    pub fn kind(&self) -> &FromZvalErrorKind {
+        &self.kind
+    }
+}
+
+impl Display for FromZvalError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "$")?;
+        for segment in &self.path {
+            segment.fmt(f)?;
+        }
+        write!(f, ": {}", self.kind)
+    }
+}
+
+impl Error for FromZvalError {}
+
+impl From<UnsupportedTypeError> for FromZvalError {
+    fn from(value: UnsupportedTypeError) -> Self {
+        Self::new(FromZvalErrorKind::Unsupported(value))
+    }
+}
+
+/// Types that can be extracted from a PHP value.
+pub trait FromZval<'a>: Sized {
+    fn from_zval(value: &Value<'a>) -> Result<Self, FromZvalError>;
+}
+
+impl<'a> FromZval<'a> for Value<'a> {
+    fn from_zval(value: &Value<'a>) -> Result<Self, FromZvalError> {
+        Ok(*value)
+    }
+}
+
+impl<'a> FromZval<'a> for bool {
+    fn from_zval(value: &Value<'a>) -> Result<Self, FromZvalError> {
+        match value {
+            Value::Bool(v) => Ok(*v),
+            _ => Err(FromZvalError::mismatch("bool", value)),
+        }
+    }
+}
+
+impl<'a> FromZval<'a> for i64 {
+    fn from_zval(value: &Value<'a>) -> Result<Self, FromZvalError> {
+        match value {
+            Value::Long(v) => Ok(*v),
+            _ => Err(FromZvalError::mismatch("int", value)),
+        }
+    }
+}
+
+macro_rules! impl_from_zval_for_integer {
+    ($($ty: ty),*) => {
+        $(
+            impl<'a> FromZval<'a> for $ty {
+                fn from_zval(value: &Value<'a>) -> Result<Self, FromZvalError> {
+                    <$ty>::try_from(i64::from_zval(value)?).map_err(|_| {
+                        FromZvalError::new(FromZvalErrorKind::OutOfRange {
+                            expected: stringify!($ty),
+                        })
+                    })
+                }
+            }
+        )*
+    };
+}
+
+impl_from_zval_for_integer!(i8, i16, i32, isize, u16, u32, u64, usize);
+
+impl<'a> FromZval<'a> for f64 {
+    /// Integers are widened to floats, as PHP does even in strict mode.
+    fn from_zval(value: &Value<'a>) -> Result<Self, FromZvalError> {
+        match value {
+            Value::Double(v) => Ok(*v),
+            Value::Long(v) => Ok(*v as f64),
+            _ => Err(FromZvalError::mismatch("float", value)),
+        }
+    }
+}
+
+impl<'a> FromZval<'a> for f32 {
+    fn from_zval(value: &Value<'a>) -> Result<Self, FromZvalError> {
+        Ok(f64::from_zval(value)? as f32)
+    }
+}
+
+impl<'a> FromZval<'a> for ZStr<'a> {
+    fn from_zval(value: &Value<'a>) -> Result<Self, FromZvalError> {
+        match value {
+            Value::String(v) => Ok(*v),
+            _ => Err(FromZvalError::mismatch("string", value)),
+        }
+    }
+}
+
+impl<'a> FromZval<'a> for &'a [u8] {
+    fn from_zval(value: &Value<'a>) -> Result<Self, FromZvalError> {
+        Ok(ZStr::from_zval(value)?.as_bytes())
+    }
+}
+
+impl<'a> FromZval<'a> for &'a str {
+    fn from_zval(value: &Value<'a>) -> Result<Self, FromZvalError> {
+        std::str::from_utf8(ZStr::from_zval(value)?.as_bytes())
+            .map_err(|_| FromZvalError::new(FromZvalErrorKind::InvalidUtf8))
+    }
+}
+
+impl<'a> FromZval<'a> for String {
+    fn from_zval(value: &Value<'a>) -> Result<Self, FromZvalError> {
+        Ok(<&str>::from_zval(value)?.to_owned())
+    }
+}
+
+impl<'a> FromZval<'a> for ZArray<'a> {
+    fn from_zval(value: &Value<'a>) -> Result<Self, FromZvalError> {
+        match value {
+            Value::Array(v) => Ok(*v),
+            _ => Err(FromZvalError::mismatch("array", value)),
+        }
+    }
+}
+
+impl<'a, T> FromZval<'a> for Option<T>
+where
+    T: FromZval<'a>,
+{
+    fn from_zval(value: &Value<'a>) -> Result<Self, FromZvalError> {
+        match value {
+            Value::Undef | Value::Null => Ok(None),
+            _ => T::from_zval(value).map(Some),
+        }
+    }
+}
+
+fn extract_elements<'a, T, F>(array: ZArray<'a>, mut f: F) -> Result<(), FromZvalError>
+where
+    T: FromZval<'a>,
+    F: FnMut(ArrayKey<'a>, T) -> Result<(), FromZvalError>,
+{
+    for element in array {
+        let key = element.key();
+        // Keys are binary strings, so the path only has to be readable.
+        let segment = match key {
+            ArrayKey::Long(h) => PathSegment::Index(h),
+            ArrayKey::String(k) => PathSegment::Key(String::from_utf8_lossy(k.as_bytes()).into()),
+        };
+
+        element
+            .value()
+            .map_err(FromZvalError::from)
+            .and_then(|v| T::from_zval(&v))
+            .and_then(|v| f(key, v))
+            .map_err(|e| e.within(segment))?;
+    }
+
+    Ok(())
+}
+
+/// The key of an element as the key of a map, failing if it is a string that
+/// is not valid UTF-8.
+fn map_key(key: ArrayKey<'_>) -> Result<String, FromZvalError> {
+    match key {
+        ArrayKey::Long(i) => Ok(i.to_string()),
+        ArrayKey::String(k) => std::str::from_utf8(k.as_bytes())
+            .map(str::to_owned)
+            .map_err(|_| FromZvalError::new(FromZvalErrorKind::InvalidUtf8)),
+    }
+}
+
+impl<'a, T> FromZval<'a> for Vec<T>
+where
+    T: FromZval<'a>,
+{
+    fn from_zval(value: &Value<'a>) -> Result<Self, FromZvalError> {
+        let array = ZArray::from_zval(value)?;
+        let mut vec = Vec::with_capacity(array.len());
+
+        extract_elements(array, |_, v| {
+            vec.push(v);
+            Ok(())
+        })?;
+        Ok(vec)
+    }
+}
+
+impl<'a, T, S> FromZval<'a> for HashMap<String, T, S>
+where
+    T: FromZval<'a>,
+    S: BuildHasher + Default,
+{
+    fn from_zval(value: &Value<'a>) -> Result<Self, FromZvalError> {
+        let array = ZArray::from_zval(value)?;
+        let mut map = HashMap::with_capacity_and_hasher(array.len(), S::default());
+
+        extract_elements(array, |k, v| {
+            map.insert(map_key(k)?, v);
+            Ok(())
+        })?;
+        Ok(map)
+    }
+}
+
+impl<'a, T> FromZval<'a> for BTreeMap<String, T>
+where
+    T: FromZval<'a>,
+{
+    fn from_zval(value: &Value<'a>) -> Result<Self, FromZvalError> {
+        let mut map = BTreeMap::new();
+
+        extract_elements(ZArray::from_zval(value)?, |k, v| {
+            map.insert(map_key(k)?, v);
+            Ok(())
+        })?;
+        Ok(map)
+    }
+}
+
+macro_rules! impl_from_zval_for_tuple {
+    ($len: expr; $($name: ident),+) => {
+        impl<'a, $($name),+> FromZval<'a> for ($($name,)+)
+        where
+            $($name: FromZval<'a>,)+
+        {
+            fn from_zval(value: &Value<'a>) -> Result<Self, FromZvalError> {
+                let array = ZArray::from_zval(value)?;
+                if array.len() != $len {
+                    return Err(FromZvalError::new(FromZvalErrorKind::Mismatch {
+                        expected: concat!("array of ", stringify!($len), " elements"),
+                        found: "array",
+                    }));
+                }
+
+                let mut elements = array.into_iter().enumerate();
+                Ok(($({
+                    let (position, element) = elements.next().unwrap();
+                    element
+                        .value()
+                        .map_err(FromZvalError::from)
+                        .and_then(|v| $name::from_zval(&v))
+                        .map_err(|e| e.within(PathSegment::Index(position as i64)))?
+                },)+))
+            }
+        }
+    };
+}
+
+impl_from_zval_for_tuple!(1; A);
+impl_from_zval_for_tuple!(2; A, B);
+impl_from_zval_for_tuple!(3; A, B, C);
+impl_from_zval_for_tuple!(4; A, B, C, D);
+impl_from_zval_for_tuple!(5; A, B, C, D, E);
+impl_from_zval_for_tuple!(6; A, B, C, D, E, F);
+impl_from_zval_for_tuple!(7; A, B, C, D, E, F, G);
+impl_from_zval_for_tuple!(8; A, B, C, D, E, F, G, H);
@@ -0,0 +1,298 @@
+//! PHP exceptions surfaced as Rust errors.
+
+use std::fmt::{Display, Formatter};
+use std::ptr;
+
+use rusty_php_sys::zend::api::zend_lookup_class_ex;
+use rusty_php_sys::zend::compile::{
+    ZEND_ACC_ENUM, ZEND_ACC_EXPLICIT_ABSTRACT_CLASS, ZEND_ACC_IMPLICIT_ABSTRACT_CLASS,
+    ZEND_ACC_INTERFACE, ZEND_ACC_TRAIT,
+};
+use rusty_php_sys::zend::exceptions::{
+    zend_ce_throwable, zend_clear_exception, zend_exception_set_previous, zend_is_unwind_exit,
+    zend_throw_error, zend_throw_exception,
+};
+use rusty_php_sys::zend::globals::eg;
+use rusty_php_sys::zend::operators::instanceof_function;
+
+use crate::diagnostic::Diagnostic;
+use crate::sapi::create_cstring;
+use crate::zend::object::ZObject;
+use crate::zend::string::ZString;
+use crate::zend::zval::OwnedZval;
+use crate::zend::Value;
+use crate::{Err, Error, Result};
+
+/// The class of the internal exception `exit()` unwinds with.
+const EXIT_CLASS: &str = "UnwindExit";
+
+/// An uncaught PHP exception, or any other `Throwable`, read out of the
+/// engine. The object itself is released, only its details are kept.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PhpException {
+    class: String,
+    message: String,
+    code: i64,
+    file: String,
+    line: i64,
+    trace: String,
+    previous: Option<Box<PhpException>>,
+}
+
+impl PhpException {
+    /// An error raised by rusty-php itself, as if PHP had thrown it.
+    pub(crate) fn new(class: &str, message: &str) -> Self {
+        Self {
+            class: class.to_string(),
+            message: message.to_string(),
+            code: 0,
+            file: String::new(),
+            line: 0,
+            trace: String::new(),
+            previous: None,
+        }
+    }
+
+    fn exit(status: i64) -> Self {
+        Self {
+            code: status,
+            ..Self::new(EXIT_CLASS, &format!("exit({})", status))
+        }
+    }
+
+    /// Takes the pending exception out of `EG(exception)`, clearing it.
+    pub(crate) fn take() -> Option<Self> {
+        let exception = unsafe { eg!(exception) };
+        if exception.is_null() {
+            return None;
+        }
+
+        // `exit()` unwinds the stack with an exception of its own.
+        if unsafe { zend_is_unwind_exit(exception) } {
+            unsafe { zend_clear_exception() };
+            return Some(Self::exit(unsafe { eg!(exit_status) } as i64));
+        }
+
+        // Hold a reference of our own, as clearing releases the engine's.
+        let exception = unsafe {
+            (*exception).gc.ref_count += 1;
+            OwnedZval::from_object(exception)
+        };
+        unsafe { zend_clear_exception() };
+
+        match exception.value() {
+            std::result::Result::Ok(Value::Object(object)) => Some(Self::from_object(object)),
+            _ => None,
+        }
+    }
+
+    fn from_object(object: ZObject<'_>) -> Self {
+        let string = |method: &str| -> String {
+            object
+                .call(method, ())
+                .ok()
+                .and_then(|v| v.extract::<String>().ok())
+                .unwrap_or_default()
+        };
+        let long = |method: &str| -> i64 {
+            object
+                .call(method, ())
+                .ok()
+                .and_then(|v| v.extract::<i64>().ok())
+                .unwrap_or_default()
+        };
+
+        let previous =
+            object
+                .call("getPrevious", ())
+                .ok()
+                .and_then(|previous| match previous.value() {
+                    std::result::Result::Ok(Value::Object(previous)) => {
+                        Some(Box::new(Self::from_object(previous)))
+                    }
+                    _ => None,
+                });
+
+        Self {
+            class: object.class_name().to_string(),
+            message: string("getMessage"),
+            code: long("getCode"),
+            file: string("getFile"),
+            line: long("getLine"),
+            trace: string("getTraceAsString"),
+            previous,
+        }
+    }
+
+    /// Whether this is the unwinding of `exit()`, with the exit status as
+    /// [`code`](Self::code), rather than a real exception.
+    pub fn is_exit(&self) -> bool {
+        self.class == EXIT_CLASS
+    }
+
+    /// The name of the class of the exception, such as `RuntimeException`.
+    pub fn class(&self) -> &str {
+        &self.class
+    }
+
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+
+    pub fn code(&self) -> i64 {
+        self.code
+    }
+
+    /// The file the exception was created in, empty if it was not thrown by
+    /// PHP code.
+    pub fn file(&self) -> &str {
+        &self.file
+    }
+
+    pub fn line(&self) -> i64 {
+        self.line
+    }
+
+    /// The stack trace, formatted like `Exception::getTraceAsString()`.
+    pub fn trace(&self) -> &str {
+        &self.trace
+    }
+
+    pub fn previous(&self) -> Option<&PhpException> {
+        self.previous.as_deref()
+    }
+}
+
+impl Display for PhpException {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}: {}", self.class, self.message)?;
+        if !self.file.is_empty() {
+            write!(f, " in {}:{}", self.file, self.line)?;
+        }
+        std::fmt::Result::Ok(())
+    }
+}
+
+/// The `ErrorException` a diagnostic would become under an error handler
+/// that throws.
+impl From<Diagnostic> for PhpException {
+    fn from(value: Diagnostic) -> Self {
+        Self {
+            file: value.file().unwrap_or_default().to_string(),
+            line: value.line() as i64,
+            ..Self::new("ErrorException", value.message())
+        }
+    }
+}
+
+impl std::error::Error for PhpException {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        self.previous
+            .as_deref()
+            .map(|e| e as &(dyn std::error::Error + 'static))
+    }
+}
+
+/// Throws an exception of class `class` into the running script, to be
+/// handled by its `try`/`catch` blocks. Always returns `Err`, which the
+/// caller should pass on to PHP. See [`Throw`] for codes and previous
+/// exceptions.
+pub fn throw<T>(class: &str, message: &str) -> Result<T> {
+    Throw::new(class, message).throw()
+}
+
+/// An exception to throw from Rust code called by PHP, such as a registered
+/// function or a SAPI callback.
+pub struct Throw {
+    class: String,
+    message: String,
+    code: i64,
+    previous: Option<OwnedZval>,
+}
+
+impl Throw {
+    /// An exception of class `class`, which must implement `Throwable`.
+    /// The class is autoloaded if needed.
+    pub fn new(class: &str, message: &str) -> Self {
+        Self {
+            class: class.to_string(),
+            message: message.to_string(),
+            code: 0,
+            previous: None,
+        }
+    }
+
+    pub fn code(mut self, code: i64) -> Self {
+        self.code = code;
+        self
+    }
+
+    /// Chains a `Throwable` object as the previous exception. Other values
+    /// are ignored.
+    pub fn previous(mut self, previous: OwnedZval) -> Self {
+        self.previous = Some(previous);
+        self
+    }
+
+    /// Throws the exception. An exception that is already pending becomes
+    /// its previous one, as in PHP.
+    ///
+    /// Returns [`Error::Thrown`], or a [`Error::Custom`] error when no PHP
+    /// code is running, as the engine cannot throw outside of a PHP frame.
+    pub fn throw<T>(self) -> Result<T> {
+        unsafe {
+            if eg!(current_execute_data).is_null() {
+                return Err(Error::custom(format!(
+                    "Cannot throw {} outside of a PHP frame",
+                    self.class
+                )));
+            }
+
+            let name = ZString::new(self.class.as_str());
+            let ce = zend_lookup_class_ex(name.as_ptr(), ptr::null_mut(), 0);
+            if ce.is_null() {
+                // The autoloader may have thrown already.
+                if eg!(exception).is_null() {
+                    let class = create_cstring(self.class.as_bytes());
+                    zend_throw_error(
+                        ptr::null_mut(),
+                        c"Class \"%s\" not found".as_ptr(),
+                        class.as_ptr(),
+                    );
+                }
+                return Err(Error::Thrown);
+            }
+
+            let abstract_flags = ZEND_ACC_INTERFACE
+                | ZEND_ACC_TRAIT
+                | ZEND_ACC_IMPLICIT_ABSTRACT_CLASS
+                | ZEND_ACC_EXPLICIT_ABSTRACT_CLASS
+                | ZEND_ACC_ENUM;
+            if !instanceof_function(ce, zend_ce_throwable) || (*ce).ce_flags & abstract_flags != 0 {
+                let class = create_cstring(self.class.as_bytes());
+                zend_throw_error(
+                    ptr::null_mut(),
+                    c"Cannot throw %s, it is not an instantiable Throwable class".as_ptr(),
+                    class.as_ptr(),
+                );
+                return Err(Error::Thrown);
+            }
+
+            let message = create_cstring(self.message.as_bytes());
+            let exception = zend_throw_exception(ce, message.as_ptr(), self.code);
+
+            if let Some(std::result::Result::Ok(Value::Object(previous))) =
+                self.previous.as_ref().map(OwnedZval::value)
+            {
+                if previous.instance_of("Throwable") {
+                    // Hands over a reference of our own.
+                    let previous = previous.as_mut_ptr();
+                    (*previous).gc.ref_count += 1;
+                    zend_exception_set_previous(exception, previous);
+                }
+            }
+        }
+
+        Err(Error::Thrown)
+    }
+}
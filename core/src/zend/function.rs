@@ -0,0 +1,470 @@
+//! Rust functions exposed to PHP.
+
+use std::collections::BTreeMap;
+use std::ffi::{c_char, CString};
+use std::panic::{catch_unwind, AssertUnwindSafe};
+use std::ptr::null;
+use std::sync::{Arc, PoisonError, RwLock};
+
+use rusty_php_sys::zend::api::{
+    zend_parse_arg_bool_weak, zend_parse_arg_double_weak, zend_parse_arg_long_weak,
+    zend_parse_arg_str_weak,
+};
+use rusty_php_sys::zend::compile::{
+    zend_call_arg, zend_call_num_args, zend_call_uses_strict_types, ZendExecuteData,
+    ZendInternalFunction,
+};
+use rusty_php_sys::zend::exceptions::{
+    zend_argument_type_error, zend_throw_error, zend_wrong_parameters_count_error,
+};
+use rusty_php_sys::zend::globals::eg;
+use rusty_php_sys::zend::type_info::{
+    MAY_BE_ANY, MAY_BE_ARRAY, MAY_BE_BOOL, MAY_BE_CALLABLE, MAY_BE_DOUBLE, MAY_BE_LONG,
+    MAY_BE_NULL, MAY_BE_OBJECT, MAY_BE_STRING,
+};
+use rusty_php_sys::zend::{
+    ZendFunctionEntry, ZendInternalArgInfo, ZendType, Zval, _ZEND_IS_VARIADIC_BIT, IS_OBJECT,
+    Z_TYPE_MASK,
+};
+
+use crate::sapi::create_cstring;
+use crate::zend::convert::{FromZval, FromZvalErrorKind, IntoZval};
+use crate::zend::object::ZObject;
+use crate::zend::string::ZStr;
+use crate::zend::zval::OwnedZval;
+use crate::zend::Value;
+use crate::{Err, Error, Ok, Result};
+
+type Handler = dyn Fn(&Arguments<'_>) -> Result<OwnedZval> + Send + Sync;
+
+struct Registered {
+    handler: Arc<Handler>,
+    required: u32,
+    max: u32,
+}
+
+/// Handlers of the registered functions and methods, by name, or by
+/// `Class::method` for methods. Every function shares the same `zif_handler`,
+/// which dispatches on the name of the called function.
+static FUNCTIONS: RwLock<BTreeMap<Vec<u8>, Registered>> = RwLock::new(BTreeMap::new());
+
+/// Forgets the registered functions and methods, once PHP is shut down.
+pub(crate) fn unregister_functions() {
+    FUNCTIONS
+        .write()
+        .unwrap_or_else(PoisonError::into_inner)
+        .clear();
+}
+
+/// The declared type of a parameter, used for reflection and error messages.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum ArgType {
+    Mixed,
+    Bool,
+    Long,
+    Double,
+    String,
+    Array,
+    Object,
+    Callable,
+}
+
+impl ArgType {
+    fn mask(self) -> u32 {
+        match self {
+            ArgType::Mixed => MAY_BE_ANY,
+            ArgType::Bool => MAY_BE_BOOL,
+            ArgType::Long => MAY_BE_LONG,
+            ArgType::Double => MAY_BE_DOUBLE,
+            ArgType::String => MAY_BE_STRING,
+            ArgType::Array => MAY_BE_ARRAY,
+            ArgType::Object => MAY_BE_OBJECT,
+            ArgType::Callable => MAY_BE_CALLABLE,
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct Arg {
+    name: String,
+    ty: Option<ArgType>,
+    nullable: bool,
+    optional: bool,
+    variadic: bool,
+    default: Option<String>,
+}
+
+impl Arg {
+    pub fn new(name: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            ty: None,
+            nullable: false,
+            optional: false,
+            variadic: false,
+            default: None,
+        }
+    }
+
+    pub fn ty(mut self, ty: ArgType) -> Self {
+        self.ty = Some(ty);
+        self
+    }
+
+    pub fn nullable(mut self) -> Self {
+        self.nullable = true;
+        self
+    }
+
+    pub fn optional(mut self) -> Self {
+        self.optional = true;
+        self
+    }
+
+    /// Marks the parameter optional, with a default shown by reflection as the
+    /// given PHP expression.
+    pub fn default(mut self, value: &str) -> Self {
+        self.optional = true;
+        self.default = Some(value.to_string());
+        self
+    }
+
+    /// Collects all remaining arguments. Must be the last parameter.
+    pub fn variadic(mut self) -> Self {
+        self.variadic = true;
+        self
+    }
+
+    fn type_mask(&self) -> u32 {
+        let mut mask = self.ty.map(ArgType::mask).unwrap_or_default();
+        if self.nullable {
+            mask |= MAY_BE_NULL;
+        }
+        if self.variadic {
+            mask |= _ZEND_IS_VARIADIC_BIT;
+        }
+        mask
+    }
+}
+
+/// A Rust function that can be registered as a global PHP function with
+/// [`PhpInit::function`](crate::PhpInit::function).
+pub struct Function {
+    name: String,
+    args: Vec<Arg>,
+    handler: Arc<Handler>,
+    pub(crate) flags: u32,
+}
+
+impl Function {
+    pub fn new<F, R>(name: &str, handler: F) -> Self
+    where
+        F: Fn(&Arguments<'_>) -> Result<R> + Send + Sync + 'static,
+        R: IntoZval,
+    {
+        Self {
+            name: name.to_string(),
+            args: Vec::new(),
+            handler: Arc::new(move |args: &Arguments<'_>| Ok(handler(args)?.into_zval())),
+            flags: 0,
+        }
+    }
+
+    pub fn arg(mut self, arg: Arg) -> Self {
+        self.args.push(arg);
+        self
+    }
+
+    fn required_args(&self) -> u32 {
+        self.args
+            .iter()
+            .take_while(|a| !a.optional && !a.variadic)
+            .count() as u32
+    }
+
+    fn max_args(&self) -> u32 {
+        match self.args.last() {
+            Some(a) if a.variadic => u32::MAX,
+            _ => self.args.len() as u32,
+        }
+    }
+}
+
+/// The arguments a PHP function was called with.
+pub struct Arguments<'a> {
+    execute_data: &'a ZendExecuteData,
+}
+
+impl<'a> Arguments<'a> {
+    pub fn len(&self) -> usize {
+        unsafe { zend_call_num_args(self.execute_data) as usize }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// The object a method was called on, `None` for functions and static
+    /// methods.
+    pub fn this(&self) -> Option<ZObject<'a>> {
+        let this = &self.execute_data.this;
+        match unsafe { this.type_info.type_info } & Z_TYPE_MASK == IS_OBJECT {
+            true => Some(unsafe { &*this.value.obj }.into()),
+            _ => None,
+        }
+    }
+
+    pub fn zval(&self, n: usize) -> Option<&'a Zval> {
+        match n < self.len() {
+            true => Some(unsafe {
+                &*zend_call_arg(
+                    self.execute_data as *const ZendExecuteData as *mut ZendExecuteData,
+                    n + 1,
+                )
+            }),
+            _ => None,
+        }
+    }
+
+    /// Converts the `n`th argument, counting from 0, like `zend_parse_parameters`
+    /// would: unless the caller declared `strict_types=1`, scalars are coerced
+    /// to the expected `bool`, `int`, `float` or `string`. Missing arguments
+    /// convert from `undef`, so they can be read as `Option`. On a mismatch a
+    /// `TypeError` is thrown and `Err` returned, which the handler should pass
+    /// on.
+    pub fn get<T>(&self, n: usize) -> Result<T>
+    where
+        T: FromZval<'a>,
+    {
+        let value = match self.zval(n).map(Value::try_from) {
+            Some(std::result::Result::Ok(v)) => v,
+            Some(_) => return throw_argument_type_error(n, "is of an unsupported type"),
+            _ => Value::Undef,
+        };
+
+        let e = match T::from_zval(&value) {
+            std::result::Result::Ok(v) => return Ok(v),
+            std::result::Result::Err(e) => e,
+        };
+        match e.kind() {
+            FromZvalErrorKind::Mismatch { expected, found } => {
+                let coerced = match e.path().is_empty() {
+                    true => self.coerce(n, expected),
+                    _ => None,
+                };
+                if let Some(std::result::Result::Ok(v)) = coerced.map(|v| T::from_zval(&v)) {
+                    return Ok(v);
+                }
+                // A deprecation turned into an exception stops the coercion.
+                if !unsafe { eg!(exception) }.is_null() {
+                    return Err(Error::Thrown);
+                }
+                throw_argument_type_error(
+                    n,
+                    &format!("must be of type {}, {} given", expected, found),
+                )
+            }
+            _ => throw_argument_type_error(n, &e.to_string()),
+        }
+    }
+
+    /// Coerces the `n`th argument to the scalar type `expected` in weak mode,
+    /// as the engine does for the parameters of internal functions.
+    fn coerce(&self, n: usize, expected: &str) -> Option<Value<'a>> {
+        let zval = self.zval(n)? as *const Zval as *mut Zval;
+        let caller = self.execute_data.prev_execute_data;
+        if caller.is_null()
+            || unsafe { (*caller).func.is_null() || zend_call_uses_strict_types(caller) }
+        {
+            return None;
+        }
+
+        let arg_num = n as u32 + 1;
+        unsafe {
+            match expected {
+                "bool" => {
+                    let mut v = false;
+                    zend_parse_arg_bool_weak(zval, &mut v, arg_num).then_some(Value::Bool(v))
+                }
+                "int" => {
+                    let mut v = 0;
+                    zend_parse_arg_long_weak(zval, &mut v, arg_num).then_some(Value::Long(v))
+                }
+                "float" => {
+                    let mut v = 0.0;
+                    zend_parse_arg_double_weak(zval, &mut v, arg_num).then_some(Value::Double(v))
+                }
+                // The argument is converted in place, so the string lives as
+                // long as the call.
+                "string" => {
+                    let mut v = std::ptr::null_mut();
+                    zend_parse_arg_str_weak(zval, &mut v, arg_num)
+                        .then(|| Value::String(ZStr::from(&*v)))
+                }
+                _ => None,
+            }
+        }
+    }
+
+    /// Collects the arguments from the `n`th on, for variadic parameters.
+    pub fn rest<T>(&self, n: usize) -> Result<Vec<T>>
+    where
+        T: FromZval<'a>,
+    {
+        let mut values = Vec::new();
+        for i in n..self.len() {
+            values.push(self.get(i)?);
+        }
+        Ok(values)
+    }
+}
+
+fn throw_argument_type_error<T>(n: usize, message: &str) -> Result<T> {
+    let message = create_cstring(message.as_bytes());
+    unsafe { zend_argument_type_error(n as u32 + 1, c"%s".as_ptr(), message.as_ptr()) };
+    Err(Error::Thrown)
+}
+
+fn registry_key(scope: Option<&[u8]>, name: &[u8]) -> Vec<u8> {
+    match scope {
+        Some(scope) => [scope, b"::", name].concat(),
+        _ => name.to_vec(),
+    }
+}
+
+unsafe extern "C" fn handle_function(execute_data: *mut ZendExecuteData, return_value: *mut Zval) {
+    let func = (*execute_data).func as *const ZendInternalFunction;
+    let name = ZStr::from(&*(*func).function_name);
+    let scope = (*func).scope.as_ref().map(|ce| ZStr::from(&*ce.name));
+    let key = registry_key(scope.map(|s| s.as_bytes()), name.as_bytes());
+
+    let (handler, required, max) = match FUNCTIONS
+        .read()
+        .unwrap_or_else(PoisonError::into_inner)
+        .get(&key)
+    {
+        Some(f) => (Arc::clone(&f.handler), f.required, f.max),
+        _ => return,
+    };
+
+    let args = Arguments {
+        execute_data: &*execute_data,
+    };
+    let num_args = args.len() as u32;
+    if num_args < required || num_args > max {
+        zend_wrong_parameters_count_error(required, max);
+        return;
+    }
+
+    match catch_unwind(AssertUnwindSafe(|| handler(&args))) {
+        std::result::Result::Ok(Ok(value)) => *return_value = value.into_raw(),
+        std::result::Result::Ok(Err(Error::Thrown)) => {}
+        // Any other failure surfaces as an `Error`, unless the handler threw.
+        std::result::Result::Ok(Err(e)) => {
+            if eg!(exception).is_null() {
+                let message = create_cstring(e.to_string().as_bytes());
+                zend_throw_error(std::ptr::null_mut(), c"%s".as_ptr(), message.as_ptr());
+            }
+        }
+        std::result::Result::Err(_) => {
+            let name = String::from_utf8_lossy(&key);
+            tracing::error!("PHP function {} panicked", name);
+
+            let name = create_cstring(name.as_bytes());
+            zend_throw_error(
+                std::ptr::null_mut(),
+                c"%s(): Rust function panicked".as_ptr(),
+                name.as_ptr(),
+            );
+        }
+    }
+}
+
+/// The `zend_function_entry` table handed to the engine, along with the
+/// memory it points to.
+pub(crate) struct FunctionTable {
+    entries: Vec<ZendFunctionEntry>,
+    _arg_infos: Vec<Vec<ZendInternalArgInfo>>,
+    _strings: Vec<CString>,
+}
+
+impl FunctionTable {
+    /// Builds the table for global functions, or for the methods of the
+    /// class named `scope`.
+    pub(crate) fn new(scope: Option<&str>, functions: Vec<Function>) -> Self {
+        let mut entries = Vec::with_capacity(functions.len() + 1);
+        let mut arg_infos = Vec::with_capacity(functions.len());
+        let mut strings = Vec::new();
+
+        let mut registry = FUNCTIONS.write().unwrap_or_else(PoisonError::into_inner);
+        for function in functions {
+            let name = create_cstring(function.name.as_bytes());
+            let required = function.required_args();
+            let max = function.max_args();
+
+            // The first entry describes the return value and carries the
+            // number of required arguments in place of a name.
+            let mut arg_info = vec![ZendInternalArgInfo {
+                name: required as usize as *const c_char,
+                ty: ZendType {
+                    ptr: std::ptr::null_mut(),
+                    type_mask: 0,
+                },
+                default_value: null(),
+            }];
+            for arg in &function.args {
+                let arg_name = create_cstring(arg.name.as_bytes());
+                let default = arg.default.as_ref().map(|d| create_cstring(d.as_bytes()));
+
+                arg_info.push(ZendInternalArgInfo {
+                    name: arg_name.as_ptr(),
+                    ty: ZendType {
+                        ptr: std::ptr::null_mut(),
+                        type_mask: arg.type_mask(),
+                    },
+                    default_value: default.as_ref().map_or(null(), |d| d.as_ptr()),
+                });
+
+                strings.push(arg_name);
+                strings.extend(default);
+            }
+
+            entries.push(ZendFunctionEntry {
+                fname: name.as_ptr(),
+                handler: Some(handle_function),
+                arg_info: arg_info.as_ptr(),
+                num_args: function.args.len() as u32,
+                flags: function.flags,
+            });
+
+            registry.insert(
+                registry_key(scope.map(str::as_bytes), function.name.as_bytes()),
+                Registered {
+                    handler: function.handler,
+                    required,
+                    max,
+                },
+            );
+            strings.push(name);
+            arg_infos.push(arg_info);
+        }
+
+        entries.push(ZendFunctionEntry {
+            fname: null(),
+            handler: None,
+            arg_info: null(),
+            num_args: 0,
+            flags: 0,
+        });
+
+        Self {
+            entries,
+            _arg_infos: arg_infos,
+            _strings: strings,
+        }
+    }
+
+    pub(crate) fn as_ptr(&self) -> *const ZendFunctionEntry {
+        self.entries.as_ptr()
+    }
+}
@@ -1,45 +1,113 @@
 //! High-level API for reading and writing Zend values.
 
 pub mod array;
+pub mod call;
+pub mod class;
+pub mod convert;
+pub mod exception;
+pub mod function;
+pub mod object;
+pub mod resource;
+#[cfg(feature = "serde")]
+pub mod serde;
 pub mod string;
+pub mod zval;
 
-use rusty_php_sys::zend::{Zval, IS_ARRAY, IS_DOUBLE, IS_LONG, IS_STRING};
+use std::error::Error;
+use std::fmt::{Display, Formatter};
+
+use rusty_php_sys::zend::{
+    Zval, IS_ARRAY, IS_DOUBLE, IS_FALSE, IS_LONG, IS_NULL, IS_OBJECT, IS_REFERENCE, IS_RESOURCE,
+    IS_STRING, IS_TRUE, IS_UNDEF, Z_TYPE_MASK,
+};
 
 use crate::zend::array::ZArray;
+use crate::zend::convert::{FromZval, FromZvalError};
+use crate::zend::object::ZObject;
+use crate::zend::resource::ZResource;
 use crate::zend::string::ZStr;
 
-#[derive(Debug, PartialEq)]
+#[derive(Copy, Clone, Debug, PartialEq)]
 pub enum Value<'a> {
+    Undef,
+    Null,
+    Bool(bool),
     Long(i64),
     Double(f64),
     String(ZStr<'a>),
     Array(ZArray<'a>),
-    // TODO: Object
-    // TODO: Resource
-    // TODO: Reference
+    Object(ZObject<'a>),
+    Resource(ZResource<'a>),
     // TODO: AstRef
-    Value(Box<Value<'a>>),
     // TODO: ClassEntry
     // TODO: Function
 }
 
-impl<'a> From<&Zval> for Value<'a> {
-    fn from(value: &Zval) -> Self {
+impl<'a> Value<'a> {
+    /// The name of the type as used in PHP diagnostics.
+    pub fn type_name(&self) -> &'static str {
+        match self {
+            Self::Undef => "undef",
+            Self::Null => "null",
+            Self::Bool(_) => "bool",
+            Self::Long(_) => "int",
+            Self::Double(_) => "float",
+            Self::String(_) => "string",
+            Self::Array(_) => "array",
+            Self::Object(_) => "object",
+            Self::Resource(_) => "resource",
+        }
+    }
+
+    pub fn extract<T>(&self) -> Result<T, FromZvalError>
+    where
+        T: FromZval<'a>,
+    {
+        T::from_zval(self)
+    }
+}
+
+/// The zval holds a type that cannot be represented as a [`Value`], such as
+/// one of the engine-internal types (`IS_INDIRECT`, `IS_PTR`, ...).
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct UnsupportedTypeError(pub u32);
+
+impl Display for UnsupportedTypeError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "unsupported zval type: {}", self.0)
+    }
+}
+
+impl Error for UnsupportedTypeError {}
+
+impl<'a> TryFrom<&Zval> for Value<'a> {
+    type Error = UnsupportedTypeError;
+
+    fn try_from(value: &Zval) -> Result<Self, Self::Error> {
         let union = &value.value;
 
         #[allow(clippy::unnecessary_cast)]
-        match unsafe { value.type_info.type_info } & 0xf {
+        Ok(match unsafe { value.type_info.type_info } & Z_TYPE_MASK {
+            IS_UNDEF => Self::Undef,
+            IS_NULL => Self::Null,
+            IS_FALSE => Self::Bool(false),
+            IS_TRUE => Self::Bool(true),
             IS_LONG => Self::Long(unsafe { union.lval } as i64),
             IS_DOUBLE => Self::Double(unsafe { union.dval } as f64),
             IS_STRING => Self::String(unsafe { &*union.str }.into()),
             IS_ARRAY => Self::Array(unsafe { &*union.arr }.into()),
-            _ => unimplemented!(),
-        }
+            IS_OBJECT => Self::Object(unsafe { &*union.obj }.into()),
+            IS_RESOURCE => Self::Resource(unsafe { &*union.res }.into()),
+            IS_REFERENCE => return Self::try_from(&unsafe { &*union.ref_ }.val),
+            ty => return Err(UnsupportedTypeError(ty)),
+        })
     }
 }
 
-impl<'a> From<Zval> for Value<'a> {
-    fn from(value: Zval) -> Self {
-        Self::from(&value)
+impl<'a> TryFrom<Zval> for Value<'a> {
+    type Error = UnsupportedTypeError;
+
+    fn try_from(value: Zval) -> Result<Self, Self::Error> {
+        Self::try_from(&value)
     }
 }
@@ -0,0 +1,216 @@
+use std::any::Any;
+use std::cell::Ref;
+use std::ptr;
+
+use rusty_php_sys::zend::api::{
+    zend_call_known_function, zend_lookup_class_ex, ZEND_FETCH_CLASS_NO_AUTOLOAD,
+};
+use rusty_php_sys::zend::globals::eg;
+use rusty_php_sys::zend::object_handlers::{ZendObjectHandlers, BP_VAR_IS, ZEND_PROPERTY_EXISTS};
+use rusty_php_sys::zend::operators::instanceof_function;
+use rusty_php_sys::zend::{ZendClassEntry, ZendObject, IS_REFERENCE, Z_TYPE_MASK};
+
+use crate::error::{Error, Stage};
+use crate::zend::call::in_frame;
+use crate::zend::class::object_state;
+use crate::zend::convert::{IntoArgs, IntoZval};
+use crate::zend::exception::PhpException;
+use crate::zend::string::{ZStr, ZString};
+use crate::zend::zval::OwnedZval;
+use crate::{Err, Ok, Result};
+
+#[derive(Copy, Clone, Debug)]
+pub struct ZObject<'a> {
+    pub raw: &'a ZendObject,
+}
+
+impl<'a> ZObject<'a> {
+    pub fn handle(&self) -> u32 {
+        self.raw.handle
+    }
+
+    pub fn class_entry(&self) -> &'a ZendClassEntry {
+        unsafe { &*self.raw.ce }
+    }
+
+    pub fn class_name(&self) -> ZStr<'a> {
+        unsafe { &*self.class_entry().name }.into()
+    }
+
+    /// Checks the object against a class or interface name, like the
+    /// `instanceof` operator. Classes that are not loaded yet are not
+    /// autoloaded.
+    pub fn instance_of(&self, class_name: &str) -> bool {
+        let name = ZString::new(class_name);
+        let ce = unsafe {
+            zend_lookup_class_ex(name.as_ptr(), ptr::null_mut(), ZEND_FETCH_CLASS_NO_AUTOLOAD)
+        };
+
+        !ce.is_null() && unsafe { instanceof_function(self.raw.ce, ce) }
+    }
+
+    /// The Rust state of an object of a [`Class`](crate::zend::class::Class)
+    /// registered from Rust, if it is a `T` and no method is changing it.
+    pub fn state<T>(&self) -> Option<Ref<'a, T>>
+    where
+        T: Any,
+    {
+        let state = unsafe { object_state(self.as_mut_ptr()) }?
+            .try_borrow()
+            .ok()?;
+        Ref::filter_map(state, |state| state.downcast_ref()).ok()
+    }
+
+    pub(crate) fn as_mut_ptr(&self) -> *mut ZendObject {
+        self.raw as *const ZendObject as *mut ZendObject
+    }
+
+    fn handlers(&self) -> &'a ZendObjectHandlers {
+        unsafe { &*self.raw.handlers }
+    }
+
+    /// Checks whether a property exists and is accessible from the current
+    /// scope, even if it is `null`.
+    pub fn has_property(&self, name: &str) -> bool {
+        let name = ZString::new(name);
+
+        match self.handlers().has_property {
+            Some(has_property) => unsafe {
+                has_property(
+                    self.as_mut_ptr(),
+                    name.as_ptr(),
+                    ZEND_PROPERTY_EXISTS,
+                    ptr::null_mut(),
+                ) != 0
+            },
+            _ => false,
+        }
+    }
+
+    /// Reads a property through the object handlers, so `__get` is honoured.
+    /// Returns `None` if the property does not exist or is not accessible
+    /// from the current scope, and fails with the exception `__get` throws.
+    pub fn get_property(&self, name: &str) -> Result<Option<OwnedZval>> {
+        let read_property = match self.handlers().read_property {
+            Some(read_property) => read_property,
+            _ => return Ok(None),
+        };
+
+        let name = ZString::new(name);
+        let mut rv = OwnedZval::undef();
+
+        // Read quietly, as `isset` does: a missing or inaccessible property
+        // without `__get` is then the shared uninitialized zval.
+        let value = in_frame(Stage::Call, || unsafe {
+            read_property(
+                self.as_mut_ptr(),
+                name.as_ptr(),
+                BP_VAR_IS,
+                ptr::null_mut(),
+                rv.as_mut_ptr(),
+            )
+        })?;
+
+        unsafe {
+            if value.is_null() || ptr::eq(value, ptr::addr_of!(eg!(uninitialized_zval))) {
+                return Ok(None);
+            }
+            if value == rv.as_mut_ptr() {
+                return Ok((!rv.is_undef()).then_some(rv));
+            }
+
+            let mut value = value;
+            if (*value).type_info.type_info & Z_TYPE_MASK == IS_REFERENCE {
+                value = &mut (*(*value).value.ref_).val;
+            }
+            Ok(Some(OwnedZval::copy_from(&*value)))
+        }
+    }
+
+    /// Writes a property through the object handlers, so `__set`, readonly
+    /// and typed properties behave as they do in PHP. Fails with the
+    /// exception the write throws, such as the `Error` of a readonly
+    /// property.
+    pub fn set_property<T>(&self, name: &str, value: T) -> Result<()>
+    where
+        T: IntoZval,
+    {
+        let name = ZString::new(name);
+        let mut value = value.into_zval();
+
+        let write_property = match self.handlers().write_property {
+            Some(write_property) => write_property,
+            _ => return Ok(()),
+        };
+
+        in_frame(Stage::Call, || unsafe {
+            write_property(
+                self.as_mut_ptr(),
+                name.as_ptr(),
+                value.as_mut_ptr(),
+                ptr::null_mut(),
+            );
+        })
+    }
+
+    /// Calls a method on the object. `__call` is used for methods that do not
+    /// exist.
+    pub fn call<A>(&self, method: &str, args: A) -> Result<OwnedZval>
+    where
+        A: IntoArgs,
+    {
+        let get_method = match self.handlers().get_method {
+            Some(get_method) => get_method,
+            _ => return Err(self.undefined_method(method)),
+        };
+
+        let name = ZString::new(method);
+        let mut args = args.into_args();
+        let mut object = self.as_mut_ptr();
+        let mut retval = OwnedZval::undef();
+
+        in_frame(Stage::Call, || unsafe {
+            let func = get_method(&mut object, name.as_ptr(), ptr::null());
+            if !func.is_null() {
+                zend_call_known_function(
+                    func,
+                    object,
+                    (*object).ce,
+                    retval.as_mut_ptr(),
+                    args.len() as u32,
+                    args.as_mut_ptr() as *mut _,
+                    ptr::null_mut(),
+                );
+            }
+        })?;
+
+        // The method was not found, or the call failed without throwing.
+        match retval.is_undef() {
+            true => Err(self.undefined_method(method)),
+            _ => Ok(retval),
+        }
+    }
+
+    fn undefined_method(&self, method: &str) -> Error {
+        Error::Exception(PhpException::new(
+            "Error",
+            &format!(
+                "Call to undefined method {}::{}()",
+                self.class_name().to_string(),
+                method
+            ),
+        ))
+    }
+}
+
+impl<'a> PartialEq for ZObject<'a> {
+    fn eq(&self, other: &Self) -> bool {
+        std::ptr::eq(self.raw, other.raw)
+    }
+}
+
+impl<'a> From<&'a ZendObject> for ZObject<'a> {
+    fn from(value: &'a ZendObject) -> Self {
+        Self { raw: value }
+    }
+}
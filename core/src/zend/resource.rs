@@ -0,0 +1,30 @@
+use std::ffi::c_int;
+
+use rusty_php_sys::zend::{ZendLong, ZendResource};
+
+#[derive(Copy, Clone, Debug)]
+pub struct ZResource<'a> {
+    pub raw: &'a ZendResource,
+}
+
+impl<'a> ZResource<'a> {
+    pub fn handle(&self) -> ZendLong {
+        self.raw.handle
+    }
+
+    pub fn ty(&self) -> c_int {
+        self.raw.ty
+    }
+}
+
+impl<'a> PartialEq for ZResource<'a> {
+    fn eq(&self, other: &Self) -> bool {
+        std::ptr::eq(self.raw, other.raw)
+    }
+}
+
+impl<'a> From<&'a ZendResource> for ZResource<'a> {
+    fn from(value: &'a ZendResource) -> Self {
+        Self { raw: value }
+    }
+}
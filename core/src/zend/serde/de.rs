@@ -0,0 +1,360 @@
+use ::serde::de::{
+    self, DeserializeSeed, EnumAccess, IntoDeserializer, MapAccess, SeqAccess, VariantAccess,
+    Visitor,
+};
+use ::serde::forward_to_deserialize_any;
+
+use crate::zend::array::{ArrayKey, ZArray, ZArrayIter};
+use crate::zend::serde::Error;
+use crate::zend::string::ZStr;
+use crate::zend::Value;
+
+pub struct Deserializer<'de> {
+    value: Value<'de>,
+}
+
+impl<'de> Deserializer<'de> {
+    pub fn new(value: Value<'de>) -> Self {
+        Self { value }
+    }
+
+    fn unexpected(&self, expected: &str) -> Error {
+        de::Error::custom(format!(
+            "expected {}, got {}",
+            expected,
+            self.value.type_name()
+        ))
+    }
+}
+
+impl<'de> From<Value<'de>> for Deserializer<'de> {
+    fn from(value: Value<'de>) -> Self {
+        Self::new(value)
+    }
+}
+
+impl<'de> From<ZArray<'de>> for Deserializer<'de> {
+    fn from(array: ZArray<'de>) -> Self {
+        Self::new(Value::Array(array))
+    }
+}
+
+fn visit_zstr<'de, V>(str: ZStr<'de>, visitor: V) -> Result<V::Value, Error>
+where
+    V: Visitor<'de>,
+{
+    match std::str::from_utf8(str.as_bytes()) {
+        Ok(s) => visitor.visit_borrowed_str(s),
+        _ => visitor.visit_borrowed_bytes(str.as_bytes()),
+    }
+}
+
+impl<'de> de::Deserializer<'de> for Deserializer<'de> {
+    type Error = Error;
+
+    fn deserialize_any<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        match self.value {
+            Value::Undef | Value::Null => visitor.visit_unit(),
+            Value::Bool(v) => visitor.visit_bool(v),
+            Value::Long(v) => visitor.visit_i64(v),
+            Value::Double(v) => visitor.visit_f64(v),
+            Value::String(v) => visit_zstr(v, visitor),
+            Value::Array(v) if v.is_packed() => visitor.visit_seq(ArrayAccess::new(v.into_iter())),
+            Value::Array(v) => visitor.visit_map(ArrayAccess::new(v.into_iter())),
+            _ => Err(self.unexpected("a scalar or an array")),
+        }
+    }
+
+    fn deserialize_bytes<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        match self.value {
+            Value::String(v) => visitor.visit_borrowed_bytes(v.as_bytes()),
+            _ => self.deserialize_any(visitor),
+        }
+    }
+
+    fn deserialize_byte_buf<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        self.deserialize_bytes(visitor)
+    }
+
+    fn deserialize_option<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        match self.value {
+            Value::Undef | Value::Null => visitor.visit_none(),
+            _ => visitor.visit_some(self),
+        }
+    }
+
+    fn deserialize_newtype_struct<V>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_seq<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        match self.value {
+            Value::Array(v) => visitor.visit_seq(ArrayAccess::new(v.into_iter())),
+            _ => Err(self.unexpected("array")),
+        }
+    }
+
+    fn deserialize_tuple<V>(self, _len: usize, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        self.deserialize_seq(visitor)
+    }
+
+    fn deserialize_tuple_struct<V>(
+        self,
+        _name: &'static str,
+        _len: usize,
+        visitor: V,
+    ) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        self.deserialize_seq(visitor)
+    }
+
+    fn deserialize_map<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        match self.value {
+            Value::Array(v) => visitor.visit_map(ArrayAccess::new(v.into_iter())),
+            _ => Err(self.unexpected("array")),
+        }
+    }
+
+    fn deserialize_struct<V>(
+        self,
+        _name: &'static str,
+        _fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        self.deserialize_map(visitor)
+    }
+
+    fn deserialize_enum<V>(
+        self,
+        _name: &'static str,
+        _variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        match self.value {
+            Value::String(v) => match std::str::from_utf8(v.as_bytes()) {
+                Ok(s) => visitor.visit_enum(s.into_deserializer()),
+                _ => Err(de::Error::custom("enum variant is not valid UTF-8")),
+            },
+            Value::Array(v) if v.len() == 1 => {
+                let element = v.into_iter().next().unwrap();
+                let variant = match element.key() {
+                    ArrayKey::String(k) => ArrayKey::String(k),
+                    _ => return Err(de::Error::custom("enum variant must be a string key")),
+                };
+                let value = element.value().map_err(de::Error::custom)?;
+
+                visitor.visit_enum(EnumDeserializer { variant, value })
+            }
+            _ => Err(self.unexpected("string or array with a single key")),
+        }
+    }
+
+    fn deserialize_ignored_any<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        visitor.visit_unit()
+    }
+
+    forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+        unit unit_struct identifier
+    }
+}
+
+struct KeyDeserializer<'de> {
+    key: ArrayKey<'de>,
+}
+
+impl<'de> de::Deserializer<'de> for KeyDeserializer<'de> {
+    type Error = Error;
+
+    fn deserialize_any<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        match self.key {
+            ArrayKey::Long(v) => visitor.visit_i64(v),
+            ArrayKey::String(v) => visit_zstr(v, visitor),
+        }
+    }
+
+    /// PHP turns numeric string keys into integers, so string-keyed maps must
+    /// accept them back as strings.
+    fn deserialize_str<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        match self.key {
+            ArrayKey::Long(v) => visitor.visit_string(v.to_string()),
+            ArrayKey::String(v) => visit_zstr(v, visitor),
+        }
+    }
+
+    fn deserialize_string<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        self.deserialize_str(visitor)
+    }
+
+    fn deserialize_identifier<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        self.deserialize_str(visitor)
+    }
+
+    forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char
+        bytes byte_buf option unit unit_struct newtype_struct seq tuple
+        tuple_struct map struct enum ignored_any
+    }
+}
+
+struct ArrayAccess<'de> {
+    iter: ZArrayIter<'de>,
+    value: Option<Value<'de>>,
+}
+
+impl<'de> ArrayAccess<'de> {
+    fn new(iter: ZArrayIter<'de>) -> Self {
+        Self { iter, value: None }
+    }
+}
+
+impl<'de> SeqAccess<'de> for ArrayAccess<'de> {
+    type Error = Error;
+
+    fn next_element_seed<T>(&mut self, seed: T) -> Result<Option<T::Value>, Self::Error>
+    where
+        T: DeserializeSeed<'de>,
+    {
+        match self.iter.next() {
+            Some(element) => {
+                let value = element.value().map_err(de::Error::custom)?;
+                seed.deserialize(Deserializer::new(value)).map(Some)
+            }
+            _ => Ok(None),
+        }
+    }
+}
+
+impl<'de> MapAccess<'de> for ArrayAccess<'de> {
+    type Error = Error;
+
+    fn next_key_seed<K>(&mut self, seed: K) -> Result<Option<K::Value>, Self::Error>
+    where
+        K: DeserializeSeed<'de>,
+    {
+        let element = match self.iter.next() {
+            Some(e) => e,
+            _ => return Ok(None),
+        };
+
+        let key = element.key();
+        self.value = Some(element.value().map_err(de::Error::custom)?);
+
+        seed.deserialize(KeyDeserializer { key }).map(Some)
+    }
+
+    fn next_value_seed<V>(&mut self, seed: V) -> Result<V::Value, Self::Error>
+    where
+        V: DeserializeSeed<'de>,
+    {
+        match self.value.take() {
+            Some(v) => seed.deserialize(Deserializer::new(v)),
+            _ => Err(de::Error::custom("value requested before its key")),
+        }
+    }
+}
+
+struct EnumDeserializer<'de> {
+    variant: ArrayKey<'de>,
+    value: Value<'de>,
+}
+
+impl<'de> EnumAccess<'de> for EnumDeserializer<'de> {
+    type Error = Error;
+    type Variant = Deserializer<'de>;
+
+    fn variant_seed<V>(self, seed: V) -> Result<(V::Value, Self::Variant), Self::Error>
+    where
+        V: DeserializeSeed<'de>,
+    {
+        let variant = seed.deserialize(KeyDeserializer { key: self.variant })?;
+        Ok((variant, Deserializer::new(self.value)))
+    }
+}
+
+impl<'de> VariantAccess<'de> for Deserializer<'de> {
+    type Error = Error;
+
+    fn unit_variant(self) -> Result<(), Self::Error> {
+        match self.value {
+            Value::Undef | Value::Null => Ok(()),
+            _ => Err(self.unexpected("null")),
+        }
+    }
+
+    fn newtype_variant_seed<T>(self, seed: T) -> Result<T::Value, Self::Error>
+    where
+        T: DeserializeSeed<'de>,
+    {
+        seed.deserialize(self)
+    }
+
+    fn tuple_variant<V>(self, _len: usize, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        de::Deserializer::deserialize_seq(self, visitor)
+    }
+
+    fn struct_variant<V>(
+        self,
+        _fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        de::Deserializer::deserialize_map(self, visitor)
+    }
+}
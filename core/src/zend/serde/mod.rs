@@ -0,0 +1,66 @@
+//! serde support for PHP values.
+//!
+//! PHP arrays are deserialised as sequences when they are packed and as maps
+//! otherwise, and Rust structs and maps serialise into associative arrays.
+
+mod de;
+mod ser;
+
+use std::fmt::{Display, Formatter};
+
+use ::serde::{Deserialize, Serialize};
+
+pub use self::de::Deserializer;
+pub use self::ser::Serializer;
+use crate::zend::zval::OwnedZval;
+use crate::zend::Value;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Error(String);
+
+impl Display for Error {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl std::error::Error for Error {}
+
+impl ::serde::de::Error for Error {
+    fn custom<T>(msg: T) -> Self
+    where
+        T: Display,
+    {
+        Self(msg.to_string())
+    }
+}
+
+impl ::serde::ser::Error for Error {
+    fn custom<T>(msg: T) -> Self
+    where
+        T: Display,
+    {
+        Self(msg.to_string())
+    }
+}
+
+pub fn from_value<'de, T>(value: Value<'de>) -> Result<T, Error>
+where
+    T: Deserialize<'de>,
+{
+    T::deserialize(Deserializer::new(value))
+}
+
+pub fn from_zval<'de, T>(zval: &'de OwnedZval) -> Result<T, Error>
+where
+    T: Deserialize<'de>,
+{
+    from_value(zval.value().map_err(|e| Error(e.to_string()))?)
+}
+
+pub fn to_zval<T>(value: &T) -> Result<OwnedZval, Error>
+where
+    T: Serialize + ?Sized,
+{
+    value.serialize(Serializer)
+}
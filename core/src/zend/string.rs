@@ -1,12 +1,37 @@
+use std::ffi::c_char;
+use std::fmt::{Debug, Formatter};
+use std::ptr::NonNull;
+
+use rusty_php_sys::zend::string::{
+    zend_new_interned_string, zend_string_copy, zend_string_hash_val, zend_string_init,
+    zend_string_init_interned, zend_string_release, zstr_is_interned,
+};
 use rusty_php_sys::zend::ZendString;
 
 use crate::ffi::char_array_as_bytes;
+use crate::zend::array::{ArrayKey, AsArrayKey};
+use crate::zend::convert::IntoZval;
+use crate::zend::zval::OwnedZval;
 
 #[derive(Copy, Clone, Debug, PartialEq, Eq)]
 pub struct ZStr<'a> {
     buf: &'a [u8],
 }
 
+impl<'a> ZStr<'a> {
+    pub fn as_bytes(&self) -> &'a [u8] {
+        self.buf
+    }
+
+    pub fn len(&self) -> usize {
+        self.buf.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.buf.is_empty()
+    }
+}
+
 impl<'a> From<&'a [u8]> for ZStr<'a> {
     fn from(value: &'a [u8]) -> Self {
         Self { buf: value }
@@ -32,3 +57,169 @@ impl<'a> ToString for ZStr<'a> {
         unsafe { String::from_utf8_unchecked(self.buf.to_vec()) }
     }
 }
+
+/// A reference to a `zend_string` owned by Rust.
+///
+/// Strings are allocated with the Zend allocator, so a `ZString` must not
+/// outlive the request it was created in unless it has been interned while
+/// the engine was starting up.
+pub struct ZString {
+    raw: NonNull<ZendString>,
+}
+
+impl ZString {
+    pub fn new<T>(bytes: T) -> Self
+    where
+        T: AsRef<[u8]>,
+    {
+        let bytes = bytes.as_ref();
+
+        unsafe {
+            Self::from_raw(zend_string_init(
+                bytes.as_ptr() as *const c_char,
+                bytes.len(),
+                false,
+            ))
+        }
+    }
+
+    /// Creates a permanent interned string, which outlives requests. Only valid
+    /// while the engine is starting up, e.g. in `MINIT`.
+    pub fn new_permanent<T>(bytes: T) -> Self
+    where
+        T: AsRef<[u8]>,
+    {
+        let bytes = bytes.as_ref();
+
+        unsafe {
+            Self::from_raw(zend_string_init_interned(
+                bytes.as_ptr() as *const c_char,
+                bytes.len(),
+                true,
+            ))
+        }
+    }
+
+    /// Takes ownership of one reference to a string.
+    ///
+    /// # Safety
+    /// The pointer must refer to a valid `zend_string`.
+    pub unsafe fn from_raw(raw: *mut ZendString) -> Self {
+        Self {
+            raw: NonNull::new_unchecked(raw),
+        }
+    }
+
+    /// Releases ownership of the string without decrementing its refcount.
+    pub fn into_raw(self) -> *mut ZendString {
+        let raw = self.raw.as_ptr();
+        std::mem::forget(self);
+        raw
+    }
+
+    pub fn as_ptr(&self) -> *mut ZendString {
+        self.raw.as_ptr()
+    }
+
+    pub fn as_zstr(&self) -> ZStr<'_> {
+        unsafe { self.raw.as_ref() }.into()
+    }
+
+    pub fn as_bytes(&self) -> &[u8] {
+        self.as_zstr().as_bytes()
+    }
+
+    pub fn len(&self) -> usize {
+        unsafe { self.raw.as_ref() }.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns the interned copy of this string, replacing it by an already
+    /// interned equal string if there is one.
+    pub fn intern(self) -> Self {
+        unsafe { Self::from_raw(zend_new_interned_string(self.into_raw())) }
+    }
+
+    pub fn is_interned(&self) -> bool {
+        unsafe { zstr_is_interned(self.as_ptr()) }
+    }
+
+    /// Returns the hash PHP uses for this string as an array key, computing and
+    /// caching it on first use.
+    pub fn hash(&self) -> u64 {
+        #[allow(clippy::unnecessary_cast)]
+        unsafe {
+            zend_string_hash_val(self.as_ptr()) as u64
+        }
+    }
+
+    /// Returns the refcount, or `None` for interned strings, which are not
+    /// refcounted.
+    pub fn ref_count(&self) -> Option<u32> {
+        match self.is_interned() {
+            true => None,
+            _ => Some(unsafe { self.raw.as_ref() }.gc.ref_count),
+        }
+    }
+}
+
+impl Clone for ZString {
+    fn clone(&self) -> Self {
+        unsafe { Self::from_raw(zend_string_copy(self.as_ptr())) }
+    }
+}
+
+impl Drop for ZString {
+    fn drop(&mut self) {
+        unsafe { zend_string_release(self.as_ptr()) };
+    }
+}
+
+impl Debug for ZString {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_tuple("ZString")
+            .field(&String::from_utf8_lossy(self.as_bytes()))
+            .finish()
+    }
+}
+
+impl PartialEq for ZString {
+    fn eq(&self, other: &Self) -> bool {
+        self.as_bytes() == other.as_bytes()
+    }
+}
+
+impl Eq for ZString {}
+
+impl From<&str> for ZString {
+    fn from(value: &str) -> Self {
+        Self::new(value)
+    }
+}
+
+impl From<&[u8]> for ZString {
+    fn from(value: &[u8]) -> Self {
+        Self::new(value)
+    }
+}
+
+impl<'a> From<ZStr<'a>> for ZString {
+    fn from(value: ZStr<'a>) -> Self {
+        Self::new(value.as_bytes())
+    }
+}
+
+impl AsArrayKey for ZString {
+    fn as_array_key(&self) -> ArrayKey<'_> {
+        ArrayKey::String(self.as_zstr())
+    }
+}
+
+impl IntoZval for ZString {
+    fn into_zval(self) -> OwnedZval {
+        unsafe { OwnedZval::from_string(self.into_raw()) }
+    }
+}
@@ -0,0 +1,228 @@
+use std::fmt::{Debug, Formatter};
+use std::mem::ManuallyDrop;
+use std::ptr;
+
+use rusty_php_sys::zend::hash::{zend_array_dup, zend_new_array};
+use rusty_php_sys::zend::string::IS_STR_INTERNED;
+use rusty_php_sys::zend::{
+    zval_ptr_dtor, ZendArray, ZendObject, ZendString, ZendValue, Zval, ZvalTypeInfoUnion,
+    GC_IMMUTABLE, IS_ARRAY, IS_ARRAY_EX, IS_DOUBLE, IS_FALSE, IS_INTERNED_STRING_EX, IS_LONG,
+    IS_NULL, IS_OBJECT_EX, IS_REFERENCE, IS_STRING_EX, IS_TRUE, IS_TYPE_REFCOUNTED, IS_UNDEF,
+    Z_TYPE_FLAGS_SHIFT, Z_TYPE_MASK,
+};
+
+use crate::zend::array::ZArrayMut;
+use crate::zend::convert::{FromZval, FromZvalError};
+use crate::zend::{UnsupportedTypeError, Value};
+
+/// A zval owned by Rust.
+///
+/// Cloning adds a reference to the underlying refcounted value and dropping
+/// releases it with `zval_ptr_dtor`, so the handle must not outlive the
+/// request it was created in.
+#[repr(transparent)]
+pub struct OwnedZval {
+    raw: Zval,
+}
+
+impl OwnedZval {
+    fn new(value: ZendValue, type_info: u32) -> Self {
+        Self {
+            raw: Zval {
+                value,
+                type_info: ZvalTypeInfoUnion { type_info },
+                u2: 0,
+            },
+        }
+    }
+
+    pub fn undef() -> Self {
+        Self::new(ZendValue { lval: 0 }, IS_UNDEF)
+    }
+
+    pub fn null() -> Self {
+        Self::new(ZendValue { lval: 0 }, IS_NULL)
+    }
+
+    pub fn bool(value: bool) -> Self {
+        match value {
+            true => Self::new(ZendValue { lval: 0 }, IS_TRUE),
+            _ => Self::new(ZendValue { lval: 0 }, IS_FALSE),
+        }
+    }
+
+    pub fn long(value: i64) -> Self {
+        #[allow(clippy::unnecessary_cast)]
+        Self::new(ZendValue { lval: value as _ }, IS_LONG)
+    }
+
+    pub fn double(value: f64) -> Self {
+        Self::new(ZendValue { dval: value }, IS_DOUBLE)
+    }
+
+    pub fn array() -> Self {
+        Self::array_with_capacity(0)
+    }
+
+    pub fn array_with_capacity(capacity: usize) -> Self {
+        unsafe { Self::from_array(zend_new_array(capacity as u32)) }
+    }
+
+    /// Wraps a string, taking ownership of one of its references.
+    ///
+    /// # Safety
+    /// The pointer must refer to a valid `zend_string`.
+    pub unsafe fn from_string(str: *mut ZendString) -> Self {
+        match (*str).gc.u.type_info & IS_STR_INTERNED != 0 {
+            true => Self::new(ZendValue { str }, IS_INTERNED_STRING_EX),
+            _ => Self::new(ZendValue { str }, IS_STRING_EX),
+        }
+    }
+
+    /// Wraps an array, taking ownership of one of its references.
+    ///
+    /// # Safety
+    /// The pointer must refer to a valid, refcounted `zend_array`.
+    pub unsafe fn from_array(arr: *mut ZendArray) -> Self {
+        Self::new(ZendValue { arr }, IS_ARRAY_EX)
+    }
+
+    /// Wraps an object, taking ownership of one of its references.
+    ///
+    /// # Safety
+    /// The pointer must refer to a valid `zend_object`.
+    pub unsafe fn from_object(obj: *mut ZendObject) -> Self {
+        Self::new(ZendValue { obj }, IS_OBJECT_EX)
+    }
+
+    /// Takes ownership of a zval, including the reference it holds.
+    ///
+    /// # Safety
+    /// The zval must be initialised and its reference must not be released
+    /// anywhere else.
+    pub unsafe fn from_raw(raw: Zval) -> Self {
+        Self { raw }
+    }
+
+    /// Copies a zval owned elsewhere, adding a reference to its value.
+    ///
+    /// # Safety
+    /// The zval must be initialised.
+    pub unsafe fn copy_from(raw: &Zval) -> Self {
+        let borrowed = ManuallyDrop::new(Self::from_raw(ptr::read(raw)));
+        Self::clone(&borrowed)
+    }
+
+    /// Releases ownership of the zval without decrementing its refcount.
+    pub fn into_raw(self) -> Zval {
+        let this = ManuallyDrop::new(self);
+        unsafe { ptr::read(&this.raw) }
+    }
+
+    pub fn as_raw(&self) -> &Zval {
+        &self.raw
+    }
+
+    /// Returns a pointer suitable as an out parameter for the engine. Any value
+    /// written through it is owned, and released, by this handle.
+    pub fn as_mut_ptr(&mut self) -> *mut Zval {
+        &mut self.raw
+    }
+
+    pub fn type_info(&self) -> u32 {
+        unsafe { self.raw.type_info.type_info }
+    }
+
+    pub fn type_code(&self) -> u32 {
+        self.type_info() & Z_TYPE_MASK
+    }
+
+    pub fn is_undef(&self) -> bool {
+        self.type_code() == IS_UNDEF
+    }
+
+    pub fn is_refcounted(&self) -> bool {
+        (self.type_info() >> Z_TYPE_FLAGS_SHIFT) & IS_TYPE_REFCOUNTED != 0
+    }
+
+    pub fn ref_count(&self) -> Option<u32> {
+        match self.is_refcounted() {
+            true => Some(unsafe { (*self.raw.value.counted).gc.ref_count }),
+            _ => None,
+        }
+    }
+
+    /// Returns the array held by this zval (or by the reference it holds) for
+    /// modification, duplicating it first if it is shared.
+    pub fn array_mut(&mut self) -> Option<ZArrayMut<'_>> {
+        let mut zval: *mut Zval = &mut self.raw;
+
+        unsafe {
+            if (*zval).type_info.type_info & Z_TYPE_MASK == IS_REFERENCE {
+                zval = &mut (*(*zval).value.ref_).val;
+            }
+
+            if (*zval).type_info.type_info & Z_TYPE_MASK != IS_ARRAY {
+                return None;
+            }
+
+            let arr = (*zval).value.arr;
+            if (*arr).gc.gc.ref_count > 1 {
+                (*zval).value.arr = zend_array_dup(arr);
+                (*zval).type_info.type_info = IS_ARRAY_EX;
+
+                if (*arr).gc.gc.u.type_info & GC_IMMUTABLE == 0 {
+                    (*arr).gc.gc.ref_count -= 1;
+                }
+            }
+
+            Some(ZArrayMut::from_raw((*zval).value.arr))
+        }
+    }
+
+    pub fn value(&self) -> Result<Value<'_>, UnsupportedTypeError> {
+        Value::try_from(&self.raw)
+    }
+
+    pub fn extract<'a, T>(&'a self) -> Result<T, FromZvalError>
+    where
+        T: FromZval<'a>,
+    {
+        T::from_zval(&self.value()?)
+    }
+}
+
+impl Clone for OwnedZval {
+    fn clone(&self) -> Self {
+        if self.is_refcounted() {
+            unsafe { (*self.raw.value.counted).gc.ref_count += 1 };
+        }
+
+        Self {
+            raw: unsafe { ptr::read(&self.raw) },
+        }
+    }
+}
+
+impl Drop for OwnedZval {
+    fn drop(&mut self) {
+        unsafe { zval_ptr_dtor(&mut self.raw) };
+    }
+}
+
+impl Debug for OwnedZval {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self.value() {
+            Ok(v) => f.debug_tuple("OwnedZval").field(&v).finish(),
+            _ => f.debug_tuple("OwnedZval").field(&self.raw).finish(),
+        }
+    }
+}
+
+impl<'a> TryFrom<&'a OwnedZval> for Value<'a> {
+    type Error = UnsupportedTypeError;
+
+    fn try_from(value: &'a OwnedZval) -> Result<Self, Self::Error> {
+        value.value()
+    }
+}
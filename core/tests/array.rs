@@ -0,0 +1,158 @@
+use rusty_php::test::TestBed;
+use rusty_php::zend::array::ArrayKey;
+use rusty_php::zend::zval::OwnedZval;
+use rusty_php::zend::Value;
+
+#[test]
+fn lookup() {
+    TestBed::run(|bed| {
+        let zval = bed.eval("['a' => 1, 5 => 'five', 'nested' => [true]]");
+        let array = match zval.value().unwrap() {
+            Value::Array(array) => array,
+            value => panic!("expected array, got {}", value.type_name()),
+        };
+
+        assert_eq!(array.get("a").unwrap(), Some(Value::Long(1)));
+        assert_eq!(array.get("5").unwrap(), Some(Value::String("five".into())));
+        assert_eq!(
+            array.get_index(5).unwrap(),
+            Some(Value::String("five".into()))
+        );
+        assert_eq!(array.get("missing").unwrap(), None);
+        assert!(array.contains_key("nested"));
+        assert!(!array.contains_key(0));
+    });
+}
+
+#[test]
+fn lookup_packed() {
+    TestBed::run(|bed| {
+        let zval = bed.eval("[10, 20, 30]");
+        let array = match zval.value().unwrap() {
+            Value::Array(array) => array,
+            value => panic!("expected array, got {}", value.type_name()),
+        };
+
+        assert_eq!(array.get(1).unwrap(), Some(Value::Long(20)));
+        assert_eq!(array.get(3).unwrap(), None);
+        assert!(!array.contains_key("a"));
+    });
+}
+
+#[test]
+fn mutate() {
+    TestBed::run(|_| {
+        let mut zval = OwnedZval::array();
+        let mut array = zval.array_mut().unwrap();
+
+        assert!(array.push("first"));
+        array.insert("name", "value");
+        array.insert("7", 7_i64);
+        assert!(array.push(8_i64));
+
+        assert_eq!(array.len(), 4);
+        assert_eq!(array.get(7).unwrap(), Some(Value::Long(7)));
+        assert_eq!(array.get(8).unwrap(), Some(Value::Long(8)));
+
+        let removed = array.remove("name").unwrap();
+        assert_eq!(removed.value().unwrap(), Value::String("value".into()));
+        assert!(array.remove("name").is_none());
+        assert_eq!(array.len(), 3);
+
+        array.clear();
+        assert!(array.is_empty());
+    });
+}
+
+#[test]
+fn mutate_separates_shared_array() {
+    TestBed::run(|bed| {
+        let original = bed.eval("array_fill(0, 3, 'x')");
+        let mut copy = original.clone();
+        assert_eq!(original.ref_count(), Some(2));
+
+        copy.array_mut().unwrap().push("y");
+
+        assert_eq!(original.ref_count(), Some(1));
+        assert_eq!(copy.ref_count(), Some(1));
+        assert_eq!(original.extract::<Vec<String>>().unwrap().len(), 3);
+        assert_eq!(copy.extract::<Vec<String>>().unwrap().len(), 4);
+    });
+}
+
+#[test]
+fn array_mut_requires_array() {
+    TestBed::run(|_| {
+        assert!(OwnedZval::long(1).array_mut().is_none());
+    });
+}
+
+#[test]
+fn iterate_skips_unset_elements() {
+    TestBed::run(|bed| {
+        let zval = bed.eval(
+            "(function () { $a = ['x' => 1, 'y' => 2, 'z' => 3]; unset($a['y']); return $a; })()",
+        );
+        let array = match zval.value().unwrap() {
+            Value::Array(array) => array,
+            value => panic!("expected array, got {}", value.type_name()),
+        };
+
+        let elements = array
+            .into_iter()
+            .map(|e| (e.key(), e.value().unwrap()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            elements,
+            vec![
+                (ArrayKey::String("x".into()), Value::Long(1)),
+                (ArrayKey::String("z".into()), Value::Long(3)),
+            ]
+        );
+    });
+}
+
+#[test]
+fn iterate_integer_keys() {
+    TestBed::run(|bed| {
+        let zval = bed.eval("[10 => 'a', -1 => 'b', 'c' => 'c']");
+        let array = match zval.value().unwrap() {
+            Value::Array(array) => array,
+            value => panic!("expected array, got {}", value.type_name()),
+        };
+
+        let keys = array.into_iter().map(|e| e.key()).collect::<Vec<_>>();
+        assert_eq!(
+            keys,
+            vec![
+                ArrayKey::Long(10),
+                ArrayKey::Long(-1),
+                ArrayKey::String("c".into())
+            ]
+        );
+    });
+}
+
+#[test]
+fn iterate_packed_with_holes() {
+    TestBed::run(|bed| {
+        let zval = bed.eval("(function () { $a = [1, 2, 3, 4]; unset($a[1]); return $a; })()");
+        let array = match zval.value().unwrap() {
+            Value::Array(array) => array,
+            value => panic!("expected array, got {}", value.type_name()),
+        };
+
+        let elements = array
+            .into_iter()
+            .map(|e| (e.key(), e.value().unwrap()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            elements,
+            vec![
+                (ArrayKey::Long(0), Value::Long(1)),
+                (ArrayKey::Long(2), Value::Long(3)),
+                (ArrayKey::Long(3), Value::Long(4)),
+            ]
+        );
+    });
+}
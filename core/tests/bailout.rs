@@ -0,0 +1,60 @@
+use rusty_php::error::Stage;
+use rusty_php::test::TestBed;
+use rusty_php::Error;
+
+#[test]
+fn fatal_error_is_an_error() {
+    TestBed::run(|bed| {
+        let e = bed
+            .try_eval("trigger_error('boom', E_USER_ERROR)")
+            .unwrap_err();
+
+        match e {
+            Error::Bailout {
+                stage: Stage::Eval,
+                last_error: Some(last_error),
+            } => assert!(last_error.contains("boom")),
+            e => panic!("expected a bailout, got: {}", e),
+        }
+    });
+}
+
+#[test]
+fn memory_limit_is_an_error() {
+    TestBed::run(|bed| {
+        let e = bed
+            .try_eval("(function () { ini_set('memory_limit', '4M'); return str_repeat('x', 1 << 24); })()")
+            .unwrap_err();
+
+        assert!(matches!(e, Error::Bailout { .. }));
+        assert!(e.to_string().contains("Allowed memory size"));
+    });
+}
+
+#[test]
+fn bailout_from_call() {
+    TestBed::run(|bed| {
+        let e = bed
+            .request()
+            .call("trigger_error", ("boom", 256_i64))
+            .unwrap_err();
+
+        assert!(matches!(
+            e,
+            Error::Bailout {
+                stage: Stage::Call,
+                ..
+            }
+        ));
+    });
+}
+
+#[test]
+fn exit_is_reported() {
+    TestBed::run(|bed| {
+        let e = bed.try_eval("exit(3)").unwrap_exception();
+
+        assert!(e.is_exit());
+        assert_eq!(e.code(), 3);
+    });
+}
@@ -0,0 +1,56 @@
+use rusty_php::test::TestBed;
+use rusty_php::zend::convert::IntoZval;
+use rusty_php::zend::Value;
+
+#[test]
+fn call_function_by_name() {
+    TestBed::run(|bed| {
+        let php = bed.request();
+
+        let ret = php.call("strtoupper", ("hello",)).unwrap();
+        assert_eq!(ret.extract::<String>().unwrap(), "HELLO");
+
+        let ret = php.call("max", [3_i64, 7, 5]).unwrap();
+        assert_eq!(ret.value().unwrap(), Value::Long(7));
+
+        let ret = php.call("implode", (",", vec!["a", "b"])).unwrap();
+        assert_eq!(ret.extract::<String>().unwrap(), "a,b");
+    });
+}
+
+#[test]
+fn call_closure() {
+    TestBed::run(|bed| {
+        let closure = bed.eval("fn ($a, $b) => $a * $b");
+
+        let ret = bed.request().call(closure, (6_i64, 7_i64)).unwrap();
+        assert_eq!(ret.value().unwrap(), Value::Long(42));
+    });
+}
+
+#[test]
+fn call_method_callable() {
+    TestBed::run(|bed| {
+        let object = bed.eval("new ArrayObject([1, 2, 3])");
+        let callable = (object, "count").into_zval();
+
+        let ret = bed.request().call(callable, ()).unwrap();
+        assert_eq!(ret.value().unwrap(), Value::Long(3));
+    });
+}
+
+#[test]
+fn call_errors() {
+    TestBed::run(|bed| {
+        let php = bed.request();
+
+        assert!(php.call("no_such_function", ()).is_err());
+
+        let thrower = bed.eval("function () { throw new RuntimeException('boom'); }");
+        assert!(php.call(thrower, ()).is_err());
+
+        // The exception was cleared, so the request is still usable.
+        let ret = php.call("strlen", ("abc",)).unwrap();
+        assert_eq!(ret.value().unwrap(), Value::Long(3));
+    });
+}
@@ -0,0 +1,61 @@
+use rusty_php::callback::{Callback, SapiCallback};
+use rusty_php::sapi::Sapi;
+use rusty_php::test::lock_engine;
+use rusty_php::zend::call::eval;
+use rusty_php::zend::Value;
+use rusty_php::PhpInit;
+
+struct PanickingCallback;
+
+impl SapiCallback for PanickingCallback {
+    fn on_get_env(&self, _name: &[u8]) -> Option<Vec<u8>> {
+        panic!("getenv is broken")
+    }
+
+    fn on_log_message(&self, _message: &[u8], _syslog_type_int: i32) {
+        panic!("logging is broken")
+    }
+}
+
+struct PanickingSapi;
+
+impl Sapi for PanickingSapi {
+    fn name(&self) -> &[u8] {
+        b"rusty-php-panicking"
+    }
+
+    fn pretty_name(&self) -> &[u8] {
+        b"Panicking SAPI for rusty-php"
+    }
+
+    fn executable_location(&self) -> &[u8] {
+        b"/opt/homebrew/bin"
+    }
+
+    fn callback(&self) -> Callback {
+        Callback::new(PanickingCallback)
+    }
+}
+
+#[test]
+fn panicking_callbacks_do_not_unwind_into_php() {
+    let _engine = lock_engine();
+    let php = PhpInit::new(PanickingSapi)
+        .init()
+        .unwrap()
+        .startup_module()
+        .unwrap()
+        .startup_request()
+        .unwrap();
+
+    // The panic in `on_getenv` reads as "not set", so PHP falls back to the
+    // process environment.
+    let ret = eval("getenv('RUSTY_PHP_SURELY_UNSET')", "callback").unwrap();
+    assert_eq!(ret.value().unwrap(), Value::Bool(false));
+
+    // Logging the warning panics too, and the request carries on.
+    let ret = eval("trigger_error('logged', E_USER_WARNING)", "callback").unwrap();
+    assert_eq!(ret.value().unwrap(), Value::Bool(true));
+
+    php.shutdown_all();
+}
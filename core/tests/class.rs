@@ -0,0 +1,174 @@
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use rusty_php::test::{TestBed, TestSapi};
+use rusty_php::zend::class::{Class, Method, Visibility};
+use rusty_php::zend::function::{Arg, ArgType};
+use rusty_php::zend::Value;
+use rusty_php::{Ok, PhpInit};
+
+#[derive(Default)]
+struct Counter {
+    count: i64,
+}
+
+static DROPPED: AtomicUsize = AtomicUsize::new(0);
+
+#[derive(Default)]
+struct Tracked;
+
+impl Drop for Tracked {
+    fn drop(&mut self) {
+        DROPPED.fetch_add(1, Ordering::SeqCst);
+    }
+}
+
+fn init(init: PhpInit<TestSapi>) -> PhpInit<TestSapi> {
+    init.class(
+        Class::<Counter>::new("Counter")
+            .implements("Countable")
+            .constant("STEP", 1)
+            .property("label", "counter", Visibility::Public)
+            .property("secret", (), Visibility::Private)
+            .static_property("instances", 0, Visibility::Public)
+            .method(
+                Method::new("__construct", |this: &mut Counter, args| {
+                    this.count = args.get::<Option<i64>>(0)?.unwrap_or_default();
+                    Ok(())
+                })
+                .arg(Arg::new("start").ty(ArgType::Long).default("0")),
+            )
+            .method(Method::new("increment", |this: &mut Counter, _| {
+                this.count += 1;
+                Ok(this.count)
+            }))
+            .method(Method::new_ref("count", |this: &Counter, _| Ok(this.count)))
+            .method(Method::new_ref("doubled", |this: &Counter, args| {
+                let count = args.this().unwrap().call("count", ())?;
+                Ok(this.count + count.extract::<i64>().unwrap_or_default())
+            }))
+            .method(Method::new("reenter", |_: &mut Counter, args| {
+                args.this().unwrap().call("increment", ())
+            }))
+            .method(Method::new_static("describe", |_| Ok("counts things"))),
+    )
+    .class(
+        Class::<Counter>::new("ResettableCounter")
+            .extends("Counter")
+            .method(Method::new("reset", |this: &mut Counter, _| {
+                this.count = 0;
+                Ok(())
+            })),
+    )
+    .class(Class::<Tracked>::new("Tracked"))
+}
+
+#[test]
+fn call_methods() {
+    TestBed::run_with(init, |bed| {
+        let counter = bed.eval("new Counter(5)");
+        let object = counter.value().unwrap();
+        let Value::Object(object) = object else {
+            panic!("expected an object, got {:?}", object);
+        };
+
+        assert_eq!(
+            object.call("increment", ()).unwrap().value().unwrap(),
+            Value::Long(6)
+        );
+        assert_eq!(object.state::<Counter>().unwrap().count, 6);
+        assert!(object.state::<Tracked>().is_none());
+
+        assert_eq!(
+            bed.eval("count(new Counter(3))").value().unwrap(),
+            Value::Long(3)
+        );
+        assert_eq!(
+            bed.eval("Counter::describe()").extract::<String>().unwrap(),
+            "counts things"
+        );
+    });
+}
+
+#[test]
+fn constants_and_properties() {
+    TestBed::run_with(init, |bed| {
+        assert_eq!(bed.eval("Counter::STEP").value().unwrap(), Value::Long(1));
+        assert_eq!(
+            bed.eval("(new Counter)->label")
+                .extract::<String>()
+                .unwrap(),
+            "counter"
+        );
+        assert_eq!(
+            bed.eval("Counter::$instances").value().unwrap(),
+            Value::Long(0)
+        );
+        assert_eq!(
+            bed.eval("(new ReflectionProperty('Counter', 'secret'))->isPrivate()")
+                .value()
+                .unwrap(),
+            Value::Bool(true)
+        );
+        assert_eq!(
+            bed.eval(
+                "(new ReflectionMethod('Counter', '__construct'))->getNumberOfRequiredParameters()"
+            )
+            .value()
+            .unwrap(),
+            Value::Long(0)
+        );
+    });
+}
+
+#[test]
+fn inheritance() {
+    TestBed::run_with(init, |bed| {
+        assert_eq!(
+            bed.eval("(function () { $c = new ResettableCounter(2); $c->reset(); return $c->increment(); })()")
+                .value()
+                .unwrap(),
+            Value::Long(1)
+        );
+        assert_eq!(
+            bed.eval("new ResettableCounter instanceof Countable")
+                .value()
+                .unwrap(),
+            Value::Bool(true)
+        );
+
+        // Classes extending a Rust class in PHP keep its state.
+        assert_eq!(
+            bed.eval("(new class(41) extends Counter {})->increment()")
+                .value()
+                .unwrap(),
+            Value::Long(42)
+        );
+    });
+}
+
+#[test]
+fn state_is_dropped_with_object() {
+    TestBed::run_with(init, |bed| {
+        let before = DROPPED.load(Ordering::SeqCst);
+        bed.eval("(function () { $a = new Tracked; $b = $a; unset($a, $b); return null; })()");
+        assert_eq!(DROPPED.load(Ordering::SeqCst), before + 1);
+    });
+}
+
+#[test]
+fn reentrant_methods() {
+    TestBed::run_with(init, |bed| {
+        assert_eq!(
+            bed.eval("(new Counter(4))->doubled()").value().unwrap(),
+            Value::Long(8)
+        );
+
+        let message = bed.eval(
+            "(function () { try { (new Counter)->reenter(); } catch (Error $e) { return $e->getMessage(); } })()",
+        );
+        assert!(message
+            .extract::<String>()
+            .unwrap()
+            .contains("Object is already in use by a running method"));
+    });
+}
@@ -0,0 +1,159 @@
+use std::collections::{BTreeMap, HashMap};
+
+use rusty_php::test::TestBed;
+use rusty_php::zend::array::ArrayKey;
+use rusty_php::zend::convert::{FromZvalErrorKind, IntoZval, PathSegment};
+use rusty_php::zend::Value;
+
+#[test]
+fn scalars_into_zval() {
+    TestBed::run(|_| {
+        assert_eq!(42_i32.into_zval().value().unwrap(), Value::Long(42));
+        assert_eq!(
+            u64::MAX.into_zval().value().unwrap(),
+            Value::Double(u64::MAX as f64)
+        );
+        assert_eq!(1.5_f64.into_zval().value().unwrap(), Value::Double(1.5));
+        assert_eq!(true.into_zval().value().unwrap(), Value::Bool(true));
+        assert_eq!(None::<i64>.into_zval().value().unwrap(), Value::Null);
+        assert_eq!(Some(7_i64).into_zval().value().unwrap(), Value::Long(7));
+    });
+}
+
+#[test]
+fn strings_into_zval() {
+    TestBed::run(|_| {
+        let zval = "Hello, world!".into_zval();
+        assert_eq!(zval.value().unwrap(), Value::String("Hello, world!".into()));
+        assert_eq!(zval.ref_count(), Some(1));
+
+        let zval = b"\0binary\xff".to_vec().into_zval();
+        assert_eq!(
+            zval.value().unwrap(),
+            Value::String(b"\0binary\xff"[..].into())
+        );
+    });
+}
+
+#[test]
+fn vec_into_zval() {
+    TestBed::run(|_| {
+        let zval = vec![1_i64, 2, 3].into_zval();
+        let array = match zval.value().unwrap() {
+            Value::Array(a) => a,
+            _ => panic!("not an array"),
+        };
+
+        assert_eq!(
+            vec![Value::Long(1), Value::Long(2), Value::Long(3)],
+            array
+                .into_iter()
+                .map(|e| e.value().unwrap())
+                .collect::<Vec<_>>(),
+        );
+    });
+}
+
+#[test]
+fn tuple_into_zval() {
+    TestBed::run(|_| {
+        let zval = (1_i64, "two", 3.0_f64).into_zval();
+        let array = match zval.value().unwrap() {
+            Value::Array(a) => a,
+            _ => panic!("not an array"),
+        };
+
+        assert_eq!(
+            vec![
+                Value::Long(1),
+                Value::String("two".into()),
+                Value::Double(3.0),
+            ],
+            array
+                .into_iter()
+                .map(|e| e.value().unwrap())
+                .collect::<Vec<_>>(),
+        );
+    });
+}
+
+#[test]
+fn map_into_zval() {
+    TestBed::run(|_| {
+        let map = BTreeMap::from([("a", vec![1_i64]), ("b", vec![2, 3])]);
+        let zval = map.into_zval();
+        let array = match zval.value().unwrap() {
+            Value::Array(a) => a,
+            _ => panic!("not an array"),
+        };
+
+        let keys = array.into_iter().map(|e| e.key()).collect::<Vec<_>>();
+        assert_eq!(
+            keys,
+            vec![ArrayKey::String("a".into()), ArrayKey::String("b".into())]
+        );
+    });
+}
+
+#[test]
+fn extract_scalars() {
+    TestBed::run(|bed| {
+        assert_eq!(bed.eval("40 + 2").extract::<i64>().unwrap(), 42);
+        assert_eq!(bed.eval("40 + 2").extract::<f64>().unwrap(), 42.0);
+        assert!(bed.eval("true").extract::<bool>().unwrap());
+        assert_eq!(bed.eval("'PHP'").extract::<String>().unwrap(), "PHP");
+        assert_eq!(bed.eval("null").extract::<Option<i64>>().unwrap(), None);
+        assert!(bed.eval("-1").extract::<u32>().is_err());
+    });
+}
+
+#[test]
+fn extract_nested() {
+    TestBed::run(|bed| {
+        let zval = bed.eval("[['name' => 'foo', 'tags' => ['a', 'b']], ['name' => 'bar']]");
+        let value = zval
+            .extract::<Vec<HashMap<String, Option<Vec<String>>>>>()
+            .unwrap_err();
+        assert_eq!(
+            value.path(),
+            &[PathSegment::Index(0), PathSegment::Key("name".into())]
+        );
+
+        let value = zval.extract::<Vec<HashMap<String, Value>>>().unwrap();
+        assert_eq!(value.len(), 2);
+        assert_eq!(value[1]["name"].extract::<String>().unwrap(), "bar");
+    });
+}
+
+#[test]
+fn extract_error_path() {
+    TestBed::run(|bed| {
+        let zval = bed.eval("[[], [], [], ['name' => 42]]");
+        let error = zval.extract::<Vec<BTreeMap<String, String>>>().unwrap_err();
+
+        assert_eq!(
+            error.kind(),
+            &FromZvalErrorKind::Mismatch {
+                expected: "string",
+                found: "int",
+            }
+        );
+        assert_eq!(
+            error.to_string(),
+            "$[3][\"name\"]: expected string, got int"
+        );
+    });
+}
+
+#[test]
+fn extract_binary_keys() {
+    TestBed::run(|bed| {
+        let zval = bed.eval("[\"\\xff\" => 'a']");
+        let error = zval.extract::<HashMap<String, String>>().unwrap_err();
+        assert_eq!(error.kind(), &FromZvalErrorKind::InvalidUtf8);
+        assert_eq!(error.path(), &[PathSegment::Key("\u{fffd}".to_string())]);
+
+        let error = zval.extract::<Vec<i64>>().unwrap_err();
+        assert_eq!(error.path(), &[PathSegment::Key("\u{fffd}".to_string())]);
+    });
+}
@@ -0,0 +1,78 @@
+use rusty_php::diagnostic::Severity;
+use rusty_php::sys::zend::errors::{E_USER_DEPRECATED, E_USER_NOTICE, E_WARNING};
+use rusty_php::test::TestBed;
+use rusty_php::zend::Value;
+
+#[test]
+fn capture_diagnostics() {
+    TestBed::run(|bed| {
+        bed.take_diagnostics();
+        bed.eval("(function () { $a = []; return $a['missing']; })()");
+
+        let diagnostics = bed.take_diagnostics();
+        assert_eq!(diagnostics.len(), 1);
+
+        let warning = &diagnostics[0];
+        assert_eq!(warning.level(), E_WARNING);
+        assert_eq!(warning.severity(), Severity::Warning);
+        assert_eq!(warning.message(), "Undefined array key \"missing\"");
+        assert_eq!(warning.file(), Some("TestBed"));
+        assert_eq!(warning.line(), 1);
+
+        assert!(bed.take_diagnostics().is_empty());
+    });
+}
+
+#[test]
+fn user_diagnostics() {
+    TestBed::run(|bed| {
+        bed.take_diagnostics();
+        bed.eval("trigger_error('note', E_USER_NOTICE) && trigger_error('old', E_USER_DEPRECATED)");
+
+        let diagnostics = bed.take_diagnostics();
+        let levels = diagnostics.iter().map(|d| d.level()).collect::<Vec<_>>();
+        assert_eq!(levels, [E_USER_NOTICE, E_USER_DEPRECATED]);
+        assert_eq!(diagnostics[1].severity(), Severity::Deprecated);
+        assert_eq!(
+            diagnostics[0].to_string(),
+            "PHP Notice:  note in TestBed on line 1"
+        );
+    });
+}
+
+#[test]
+fn diagnostics_seen_despite_error_handler() {
+    TestBed::run(|bed| {
+        bed.take_diagnostics();
+        bed.eval("(function () { set_error_handler(fn () => true); return @$undefined; })()");
+
+        assert_eq!(bed.take_diagnostics().len(), 1);
+    });
+}
+
+#[test]
+fn strict_mode() {
+    TestBed::run(|bed| {
+        bed.set_strict(true);
+
+        let e = bed.try_eval("intdiv(1, 1) + $undefined").unwrap_exception();
+        assert_eq!(e.class(), "ErrorException");
+        assert_eq!(e.message(), "Undefined variable $undefined");
+
+        assert_eq!(bed.eval("1 + 1").value().unwrap(), Value::Long(2));
+
+        bed.set_strict(false);
+        assert_eq!(bed.eval("1 + $undefined").value().unwrap(), Value::Long(1));
+    });
+}
+
+#[test]
+fn binary_diagnostic_message() {
+    TestBed::run(|bed| {
+        bed.take_diagnostics();
+        bed.eval("(function () { $a = []; return $a[\"\\xff\"]; })()");
+
+        let diagnostics = bed.take_diagnostics();
+        assert_eq!(diagnostics[0].message(), "Undefined array key \"\u{fffd}\"");
+    });
+}
@@ -0,0 +1,154 @@
+use std::error::Error;
+
+use rusty_php::test::{TestBed, TestSapi};
+use rusty_php::zend::exception::{throw, Throw};
+use rusty_php::zend::function::{Arg, Function};
+use rusty_php::zend::zval::OwnedZval;
+use rusty_php::zend::Value;
+use rusty_php::PhpInit;
+
+fn init(init: PhpInit<TestSapi>) -> PhpInit<TestSapi> {
+    init.function(
+        Function::new("rust_throw", |args| -> rusty_php::Result<()> {
+            throw(&args.get::<String>(0)?, "thrown from Rust")
+        })
+        .arg(Arg::new("class")),
+    )
+    .function(
+        Function::new("rust_throw_chained", |args| -> rusty_php::Result<()> {
+            let previous = unsafe { OwnedZval::copy_from(args.zval(0).unwrap()) };
+            Throw::new("LogicException", "outer")
+                .code(7)
+                .previous(previous)
+                .throw()
+        })
+        .arg(Arg::new("previous")),
+    )
+}
+
+#[test]
+fn uncaught_exception_details() {
+    TestBed::run(|bed| {
+        let e = bed
+            .try_eval("(function () { throw new RuntimeException('boom', 42); })()")
+            .unwrap_exception();
+
+        assert_eq!(e.class(), "RuntimeException");
+        assert_eq!(e.message(), "boom");
+        assert_eq!(e.code(), 42);
+        assert_eq!(e.file(), "TestBed");
+        assert_eq!(e.line(), 1);
+        assert!(e.trace().contains("{closure}()"));
+        assert!(e.previous().is_none());
+        assert_eq!(e.to_string(), "RuntimeException: boom in TestBed:1");
+    });
+}
+
+#[test]
+fn previous_exception() {
+    TestBed::run(|bed| {
+        let e = bed
+            .try_eval("throw new LogicException('outer', 0, new InvalidArgumentException('inner'))")
+            .unwrap_exception();
+
+        assert_eq!(e.class(), "LogicException");
+        let previous = e.previous().unwrap();
+        assert_eq!(previous.class(), "InvalidArgumentException");
+        assert_eq!(previous.message(), "inner");
+        assert_eq!(e.source().unwrap().to_string(), previous.to_string());
+    });
+}
+
+#[test]
+fn errors_and_parse_errors() {
+    TestBed::run(|bed| {
+        let e = bed.try_eval("intdiv(1, 0)").unwrap_exception();
+        assert_eq!(e.class(), "DivisionByZeroError");
+        assert_eq!(e.message(), "Division by zero");
+
+        let e = bed.try_eval("1 +").unwrap_exception();
+        assert_eq!(e.class(), "ParseError");
+    });
+}
+
+#[test]
+fn call_errors_carry_exception() {
+    TestBed::run(|bed| {
+        let php = bed.request();
+
+        let e = php.call("no_such_function", ()).unwrap_exception();
+        assert_eq!(e.class(), "Error");
+        assert!(e.message().contains("no_such_function"));
+
+        let object = bed.eval("new ArrayObject()");
+        let Value::Object(object) = object.value().unwrap() else {
+            unreachable!()
+        };
+        let e = object.call("missing", ()).unwrap_exception();
+        assert_eq!(
+            e.message(),
+            "Call to undefined method ArrayObject::missing()"
+        );
+    });
+}
+
+#[test]
+fn request_usable_after_exception() {
+    TestBed::run(|bed| {
+        for _ in 0..3 {
+            assert!(bed.try_eval("throw new Exception('again')").is_err());
+        }
+
+        let ret = bed.eval("strlen('abc')");
+        assert_eq!(ret.value().unwrap(), Value::Long(3));
+    });
+}
+
+#[test]
+fn throw_from_rust_is_catchable() {
+    TestBed::run_with(init, |bed| {
+        let ret = bed.eval(
+            "(function () {
+                try {
+                    rust_throw('InvalidArgumentException');
+                } catch (InvalidArgumentException $e) {
+                    return get_class($e) . ': ' . $e->getMessage();
+                }
+            })()",
+        );
+        assert_eq!(
+            ret.extract::<String>().unwrap(),
+            "InvalidArgumentException: thrown from Rust"
+        );
+    });
+}
+
+#[test]
+fn throw_with_code_and_previous() {
+    TestBed::run_with(init, |bed| {
+        let e = bed
+            .try_eval("rust_throw_chained(new RuntimeException('inner'))")
+            .unwrap_exception();
+
+        assert_eq!(e.class(), "LogicException");
+        assert_eq!(e.code(), 7);
+        assert_eq!(e.previous().unwrap().message(), "inner");
+    });
+}
+
+#[test]
+fn throw_invalid_class() {
+    TestBed::run_with(init, |bed| {
+        let e = bed
+            .try_eval("rust_throw('NoSuchException')")
+            .unwrap_exception();
+        assert_eq!(e.class(), "Error");
+        assert_eq!(e.message(), "Class \"NoSuchException\" not found");
+
+        let e = bed.try_eval("rust_throw('ArrayObject')").unwrap_exception();
+        assert_eq!(e.class(), "Error");
+
+        let e = bed.try_eval("rust_throw('Throwable')").unwrap_exception();
+        assert_eq!(e.class(), "Error");
+    });
+}
@@ -0,0 +1,86 @@
+use std::path::PathBuf;
+
+use rusty_php::error::Stage;
+use rusty_php::test::TestBed;
+use rusty_php::zend::Value;
+use rusty_php::Error;
+
+fn script(name: &str, contents: &str) -> PathBuf {
+    let path = std::env::temp_dir().join(format!("rusty-php-{}-{}.php", name, std::process::id()));
+    std::fs::write(&path, contents).unwrap();
+    path
+}
+
+#[test]
+fn eval() {
+    TestBed::run(|bed| {
+        let ret = bed.request().eval("strtoupper('abc')").unwrap();
+        assert_eq!(ret.value().unwrap(), Value::String("ABC".into()));
+
+        let e = bed
+            .request()
+            .eval("throw new LogicException('nope')")
+            .unwrap_exception();
+        assert_eq!(e.class(), "LogicException");
+    });
+}
+
+#[test]
+fn execute() {
+    let path = script("execute", "<?php\n$GLOBALS['answer'] = 40 + 2;\n");
+
+    TestBed::run(|bed| {
+        bed.request().execute(&path).unwrap();
+        assert_eq!(
+            bed.eval("$GLOBALS['answer']").value().unwrap(),
+            Value::Long(42)
+        );
+        assert_eq!(
+            bed.eval("get_included_files()[0]").value().unwrap(),
+            Value::String(path.to_str().unwrap().into())
+        );
+    });
+
+    std::fs::remove_file(path).unwrap();
+}
+
+#[test]
+fn execute_exit() {
+    let path = script("exit", "<?php\nexit(3);\n");
+
+    TestBed::run(|bed| bed.request().execute(&path).unwrap());
+
+    std::fs::remove_file(path).unwrap();
+}
+
+#[test]
+fn execute_exit_after_fatal_error() {
+    let uncaught = script("fatal", "<?php\nthrow new RuntimeException('nope');\n");
+    let bare = script("bare-exit", "<?php\nexit;\n");
+    let status = script("exit-255", "<?php\nexit(255);\n");
+
+    TestBed::run(|bed| {
+        assert!(bed.request().execute(&uncaught).is_err());
+        bed.request().execute(&bare).unwrap();
+        bed.request().execute(&status).unwrap();
+    });
+
+    for path in [uncaught, bare, status] {
+        std::fs::remove_file(path).unwrap();
+    }
+}
+
+#[test]
+fn execute_uncaught_exception() {
+    let path = script("uncaught", "<?php\nthrow new RuntimeException('nope');\n");
+
+    TestBed::run(|bed| match bed.request().execute(&path).unwrap_err() {
+        Error::Stage {
+            stage: Stage::Execute,
+            last_error: Some(last_error),
+        } => assert!(last_error.contains("Uncaught RuntimeException: nope")),
+        e => panic!("expected a failed execution, got: {}", e),
+    });
+
+    std::fs::remove_file(path).unwrap();
+}
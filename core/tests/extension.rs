@@ -0,0 +1,98 @@
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use rusty_php::extension::{Extension, ModuleInfo};
+use rusty_php::test::TestBed;
+use rusty_php::zend::function::Function;
+use rusty_php::zend::Value;
+use rusty_php::Ok;
+
+static MODULE_STARTUPS: AtomicUsize = AtomicUsize::new(0);
+static MODULE_SHUTDOWNS: AtomicUsize = AtomicUsize::new(0);
+static REQUEST_STARTUPS: AtomicUsize = AtomicUsize::new(0);
+static REQUEST_SHUTDOWNS: AtomicUsize = AtomicUsize::new(0);
+
+struct Demo;
+
+impl Extension for Demo {
+    fn name(&self) -> &str {
+        "rusty-demo"
+    }
+
+    fn version(&self) -> &str {
+        "1.2.3"
+    }
+
+    fn functions(&self) -> Vec<Function> {
+        vec![Function::new("rusty_demo_hello", |_| Ok("hello"))]
+    }
+
+    fn module_startup(&self) -> rusty_php::Result<()> {
+        MODULE_STARTUPS.fetch_add(1, Ordering::SeqCst);
+        Ok(())
+    }
+
+    fn module_shutdown(&self) -> rusty_php::Result<()> {
+        MODULE_SHUTDOWNS.fetch_add(1, Ordering::SeqCst);
+        Ok(())
+    }
+
+    fn request_startup(&self) -> rusty_php::Result<()> {
+        REQUEST_STARTUPS.fetch_add(1, Ordering::SeqCst);
+        Ok(())
+    }
+
+    fn request_shutdown(&self) -> rusty_php::Result<()> {
+        REQUEST_SHUTDOWNS.fetch_add(1, Ordering::SeqCst);
+        Ok(())
+    }
+
+    fn info(&self, info: &mut ModuleInfo) {
+        info.header("rusty-demo support", "enabled");
+        info.row("Version", self.version());
+    }
+}
+
+#[test]
+fn extension_lifecycle() {
+    let bed = TestBed::startup_with(|init| init.extension(Demo));
+    assert_eq!(MODULE_STARTUPS.load(Ordering::SeqCst), 1);
+    assert_eq!(REQUEST_STARTUPS.load(Ordering::SeqCst), 1);
+
+    assert_eq!(
+        bed.eval("extension_loaded('rusty-demo')").value().unwrap(),
+        Value::Bool(true)
+    );
+    assert_eq!(
+        bed.eval("in_array('rusty-demo', get_loaded_extensions())")
+            .value()
+            .unwrap(),
+        Value::Bool(true)
+    );
+    assert_eq!(
+        bed.eval("phpversion('rusty-demo')")
+            .extract::<String>()
+            .unwrap(),
+        "1.2.3"
+    );
+    assert_eq!(
+        bed.eval("rusty_demo_hello()").extract::<String>().unwrap(),
+        "hello"
+    );
+    assert_eq!(
+        bed.eval("in_array('rusty_demo_hello', get_extension_funcs('rusty-demo'))")
+            .value()
+            .unwrap(),
+        Value::Bool(true)
+    );
+
+    let info = bed
+        .eval("(function () { ob_start(); phpinfo(INFO_MODULES); return ob_get_clean(); })()")
+        .extract::<String>()
+        .unwrap();
+    assert!(info.contains("rusty-demo support => enabled"));
+    assert!(info.contains("Version => 1.2.3"));
+
+    bed.shutdown();
+    assert_eq!(REQUEST_SHUTDOWNS.load(Ordering::SeqCst), 1);
+    assert_eq!(MODULE_SHUTDOWNS.load(Ordering::SeqCst), 1);
+}
@@ -0,0 +1,170 @@
+use rusty_php::test::{TestBed, TestSapi};
+use rusty_php::zend::function::{Arg, ArgType, Function};
+use rusty_php::zend::Value;
+use rusty_php::{Err, Error, Ok, PhpInit};
+
+fn functions() -> Vec<Function> {
+    vec![
+        Function::new("rust_add", |args| {
+            Ok(args.get::<i64>(0)? + args.get::<i64>(1)?)
+        })
+        .arg(Arg::new("a").ty(ArgType::Long))
+        .arg(Arg::new("b").ty(ArgType::Long)),
+        Function::new("rust_greet", |args| {
+            let name = args.get::<Option<String>>(0)?;
+            Ok(format!("Hello, {}!", name.as_deref().unwrap_or("world")))
+        })
+        .arg(Arg::new("name").ty(ArgType::String).default("null")),
+        Function::new("rust_sum", |args| {
+            Ok(args.rest::<i64>(0)?.iter().sum::<i64>())
+        })
+        .arg(Arg::new("numbers").ty(ArgType::Long).variadic()),
+        Function::new("rust_panic", |_| -> rusty_php::Result<()> {
+            panic!("oops")
+        }),
+        Function::new("rust_fail", |_| -> rusty_php::Result<()> {
+            Err(Error::custom("something went wrong"))
+        }),
+    ]
+}
+
+fn init(init: PhpInit<TestSapi>) -> PhpInit<TestSapi> {
+    functions().into_iter().fold(init, PhpInit::function)
+}
+
+#[test]
+fn call_registered_function() {
+    TestBed::run_with(init, |bed| {
+        assert_eq!(bed.eval("rust_add(2, 3)").value().unwrap(), Value::Long(5));
+
+        let ret = bed.request().call("rust_add", (40_i64, 2_i64)).unwrap();
+        assert_eq!(ret.value().unwrap(), Value::Long(42));
+    });
+}
+
+#[test]
+fn optional_and_variadic_arguments() {
+    TestBed::run_with(init, |bed| {
+        assert_eq!(
+            bed.eval("rust_greet()").extract::<String>().unwrap(),
+            "Hello, world!"
+        );
+        assert_eq!(
+            bed.eval("rust_greet('PHP')").extract::<String>().unwrap(),
+            "Hello, PHP!"
+        );
+
+        assert_eq!(bed.eval("rust_sum()").value().unwrap(), Value::Long(0));
+        assert_eq!(
+            bed.eval("rust_sum(1, 2, 3, 4)").value().unwrap(),
+            Value::Long(10)
+        );
+    });
+}
+
+#[test]
+fn argument_errors_throw() {
+    TestBed::run_with(init, |bed| {
+        let message = bed.eval(
+            "(function () { try { rust_add(1, 'x'); } catch (TypeError $e) { return $e->getMessage(); } })()",
+        );
+        assert_eq!(
+            message.extract::<String>().unwrap(),
+            "rust_add(): Argument #2 ($b) must be of type int, string given"
+        );
+
+        let message = bed.eval(
+            "(function () { try { rust_add(1); } catch (ArgumentCountError $e) { return $e->getMessage(); } })()",
+        );
+        assert_eq!(
+            message.extract::<String>().unwrap(),
+            "rust_add() expects exactly 2 arguments, 1 given"
+        );
+
+        assert!(bed.request().call("rust_add", (1_i64,)).is_err());
+    });
+}
+
+#[test]
+fn weak_mode_coerces_scalars() {
+    TestBed::run_with(init, |bed| {
+        assert_eq!(
+            bed.eval("rust_add('40', 2.0)").value().unwrap(),
+            Value::Long(42)
+        );
+        assert_eq!(
+            bed.eval("rust_greet(42)").extract::<String>().unwrap(),
+            "Hello, 42!"
+        );
+
+        let message = bed.eval(
+            "(function () { try { return eval('declare(strict_types=1); return rust_add(1, \\'2\\');'); } catch (TypeError $e) { return $e->getMessage(); } })()",
+        );
+        assert_eq!(
+            message.extract::<String>().unwrap(),
+            "rust_add(): Argument #2 ($b) must be of type int, string given"
+        );
+    });
+}
+
+#[test]
+fn panic_throws_error() {
+    TestBed::run_with(init, |bed| {
+        let message = bed.eval(
+            "(function () { try { rust_panic(); } catch (Error $e) { return $e->getMessage(); } })()",
+        );
+        assert_eq!(
+            message.extract::<String>().unwrap(),
+            "rust_panic(): Rust function panicked"
+        );
+    });
+}
+
+#[test]
+fn failing_function_throws() {
+    TestBed::run_with(init, |bed| {
+        let e = bed.try_eval("rust_fail()").unwrap_exception();
+        assert_eq!(e.class(), "Error");
+        assert_eq!(e.message(), "something went wrong");
+
+        let e = Error::from(e);
+        assert!(e.exception().is_some());
+        assert_eq!(
+            e.to_string(),
+            "Uncaught Error: something went wrong in TestBed:1"
+        );
+    });
+}
+
+#[test]
+fn reflect_parameters() {
+    TestBed::run_with(init, |bed| {
+        let names = bed.eval(
+            "array_map(fn ($p) => $p->getName(), (new ReflectionFunction('rust_add'))->getParameters())",
+        );
+        assert_eq!(names.extract::<Vec<String>>().unwrap(), ["a", "b"]);
+
+        let function = "new ReflectionFunction('rust_greet')";
+        assert_eq!(
+            bed.eval(&format!("({})->getNumberOfRequiredParameters()", function))
+                .value()
+                .unwrap(),
+            Value::Long(0)
+        );
+        assert_eq!(
+            bed.eval(&format!(
+                "({})->getParameters()[0]->getDefaultValue()",
+                function
+            ))
+            .value()
+            .unwrap(),
+            Value::Null
+        );
+        assert_eq!(
+            bed.eval("(new ReflectionFunction('rust_sum'))->isVariadic()")
+                .value()
+                .unwrap(),
+            Value::Bool(true)
+        );
+    });
+}
@@ -0,0 +1,56 @@
+use rusty_php::test::{lock_engine, TestSapi};
+use rusty_php::zend::Value;
+use rusty_php::{Error, PhpInit};
+
+#[test]
+fn restart() {
+    let _engine = lock_engine();
+
+    for i in 0..3 {
+        let php = PhpInit::new(TestSapi)
+            .init()
+            .unwrap()
+            .startup_module()
+            .unwrap()
+            .startup_request()
+            .unwrap();
+
+        let ret = php.eval(&format!("{} + 1", i)).unwrap();
+        assert_eq!(ret.value().unwrap(), Value::Long(i + 1));
+        php.shutdown_all();
+    }
+}
+
+#[test]
+fn start_twice() {
+    let _engine = lock_engine();
+
+    let php = PhpInit::new(TestSapi).init().unwrap();
+    let e = PhpInit::new(TestSapi).init().unwrap_err();
+    assert!(matches!(e, Error::Running));
+
+    php.shutdown();
+    PhpInit::new(TestSapi).init().unwrap().shutdown();
+}
+
+#[test]
+fn drop_shuts_down() {
+    let _engine = lock_engine();
+
+    let php = PhpInit::new(TestSapi)
+        .init()
+        .unwrap()
+        .startup_module()
+        .unwrap()
+        .startup_request()
+        .unwrap();
+    php.eval("1").unwrap();
+    drop(php);
+
+    PhpInit::new(TestSapi)
+        .init()
+        .unwrap()
+        .startup_module()
+        .unwrap()
+        .shutdown_all();
+}
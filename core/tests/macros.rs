@@ -0,0 +1,151 @@
+#![cfg(feature = "macros")]
+
+use rusty_php::test::TestBed;
+use rusty_php::zend::Value;
+
+#[rusty_php::php_module]
+mod plain {
+    use rusty_php::{php_class, php_function, php_methods};
+
+    #[php_function]
+    pub fn macro_add(a: i64, b: i64) -> i64 {
+        a + b
+    }
+
+    #[php_function(name = "macro_greet")]
+    pub fn greet(name: &str, greeting: Option<String>) -> String {
+        format!("{}, {}!", greeting.as_deref().unwrap_or("Hello"), name)
+    }
+
+    #[php_function]
+    pub fn macro_checked(value: i64) -> rusty_php::Result<i64> {
+        match value >= 0 {
+            true => rusty_php::Ok(value),
+            _ => rusty_php::Err(rusty_php::Error::custom("value must not be negative")),
+        }
+    }
+
+    #[php_class(implements = "Countable")]
+    #[derive(Default)]
+    pub struct MacroCounter {
+        count: i64,
+    }
+
+    #[php_methods]
+    impl MacroCounter {
+        pub const STEP: i64 = 1;
+
+        pub fn __construct(&mut self, start: Option<i64>) {
+            self.count = start.unwrap_or_default();
+        }
+
+        pub fn increment(&mut self) -> i64 {
+            self.count += Self::STEP;
+            self.count
+        }
+
+        pub fn count(&self) -> i64 {
+            self.count
+        }
+
+        pub fn zero() -> i64 {
+            0
+        }
+
+        #[allow(dead_code)]
+        fn hidden(&self) {}
+    }
+}
+
+#[rusty_php::php_module(name = "macro-demo", version = "0.1.0")]
+mod extension {
+    use rusty_php::php_function;
+
+    #[php_function]
+    pub fn macro_demo_version() -> &'static str {
+        "0.1.0"
+    }
+}
+
+#[test]
+fn module_functions() {
+    TestBed::run_with(plain::register, |bed| {
+        assert_eq!(bed.eval("macro_add(2, 3)").value().unwrap(), Value::Long(5));
+        assert_eq!(
+            bed.eval("macro_greet('PHP')").extract::<String>().unwrap(),
+            "Hello, PHP!"
+        );
+        assert_eq!(
+            bed.eval("macro_greet('PHP', 'Hi')")
+                .extract::<String>()
+                .unwrap(),
+            "Hi, PHP!"
+        );
+        assert_eq!(
+            bed.eval("(new ReflectionFunction('macro_greet'))->getNumberOfRequiredParameters()")
+                .value()
+                .unwrap(),
+            Value::Long(1)
+        );
+        assert_eq!(
+            bed.eval(
+                "(string) (new ReflectionFunction('macro_add'))->getParameters()[1]->getType()"
+            )
+            .extract::<String>()
+            .unwrap(),
+            "int"
+        );
+        assert_eq!(
+            bed.eval("macro_checked(4)").value().unwrap(),
+            Value::Long(4)
+        );
+
+        let e = bed.try_eval("macro_checked(-1)").unwrap_exception();
+        assert_eq!(e.class(), "Error");
+        assert_eq!(e.message(), "value must not be negative");
+    });
+}
+
+#[test]
+fn module_classes() {
+    TestBed::run_with(plain::register, |bed| {
+        assert_eq!(
+            bed.eval(
+                "(function () { $c = new MacroCounter(1); $c->increment(); return count($c); })()"
+            )
+            .value()
+            .unwrap(),
+            Value::Long(2)
+        );
+        assert_eq!(
+            bed.eval("MacroCounter::STEP + MacroCounter::zero()")
+                .value()
+                .unwrap(),
+            Value::Long(1)
+        );
+        assert_eq!(
+            bed.eval("method_exists('MacroCounter', 'hidden')")
+                .value()
+                .unwrap(),
+            Value::Bool(false)
+        );
+    });
+}
+
+#[test]
+fn module_as_extension() {
+    TestBed::run_with(extension::register, |bed| {
+        assert_eq!(
+            bed.eval("phpversion('macro-demo')")
+                .extract::<String>()
+                .unwrap(),
+            "0.1.0"
+        );
+        assert_eq!(
+            bed.eval("get_extension_funcs('macro-demo')")
+                .extract::<Vec<String>>()
+                .unwrap(),
+            ["macro_demo_version"]
+        );
+    });
+}
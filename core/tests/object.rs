@@ -0,0 +1,155 @@
+use rusty_php::test::TestBed;
+use rusty_php::zend::Value;
+
+const DTO: &str = r#"new class {
+    public string $name = 'alice';
+    public ?int $age = null;
+    private string $secret = 'hidden';
+
+    public function greet(string $greeting): string {
+        return "{$greeting}, {$this->name}!";
+    }
+}"#;
+
+#[test]
+fn class_name() {
+    TestBed::run(|bed| {
+        let zval = bed.eval("new ArrayObject([])");
+        let object = match zval.value().unwrap() {
+            Value::Object(object) => object,
+            value => panic!("expected object, got {}", value.type_name()),
+        };
+
+        assert_eq!(object.class_name().to_string(), "ArrayObject");
+        assert!(object.instance_of("ArrayObject"));
+        assert!(object.instance_of("countable"));
+        assert!(!object.instance_of("stdClass"));
+        assert!(!object.instance_of("NoSuchClass"));
+    });
+}
+
+#[test]
+fn read_properties() {
+    TestBed::run(|bed| {
+        let zval = bed.eval(DTO);
+        let object = match zval.value().unwrap() {
+            Value::Object(object) => object,
+            value => panic!("expected object, got {}", value.type_name()),
+        };
+
+        let name = object.get_property("name").unwrap().unwrap();
+        assert_eq!(name.value().unwrap(), Value::String("alice".into()));
+        assert_eq!(
+            object
+                .get_property("age")
+                .unwrap()
+                .unwrap()
+                .value()
+                .unwrap(),
+            Value::Null
+        );
+        assert!(object.has_property("age"));
+        assert!(object.get_property("secret").unwrap().is_none());
+        assert!(object.get_property("missing").unwrap().is_none());
+    });
+}
+
+#[test]
+fn write_properties() {
+    TestBed::run(|bed| {
+        let zval = bed.eval(DTO);
+        let object = match zval.value().unwrap() {
+            Value::Object(object) => object,
+            value => panic!("expected object, got {}", value.type_name()),
+        };
+
+        object.set_property("name", "bob").unwrap();
+        object.set_property("age", 41_i64).unwrap();
+
+        assert_eq!(
+            object
+                .get_property("name")
+                .unwrap()
+                .unwrap()
+                .extract::<String>()
+                .unwrap(),
+            "bob"
+        );
+        assert_eq!(
+            object
+                .get_property("age")
+                .unwrap()
+                .unwrap()
+                .extract::<i64>()
+                .unwrap(),
+            41
+        );
+    });
+}
+
+#[test]
+fn magic_get_without_isset() {
+    TestBed::run(|bed| {
+        let zval = bed.eval(
+            "new class { public function __get($name) { return $name === 'answer' ? 42 : throw new LogicException($name); } }",
+        );
+        let object = match zval.value().unwrap() {
+            Value::Object(object) => object,
+            value => panic!("expected object, got {}", value.type_name()),
+        };
+
+        let answer = object.get_property("answer").unwrap().unwrap();
+        assert_eq!(answer.value().unwrap(), Value::Long(42));
+
+        let e = object.get_property("other").unwrap_exception();
+        assert_eq!(e.class(), "LogicException");
+    });
+}
+
+#[test]
+fn write_readonly_property() {
+    TestBed::run(|bed| {
+        let zval =
+            bed.eval("new class { public function __construct(public readonly int $id = 1) {} }");
+        let object = match zval.value().unwrap() {
+            Value::Object(object) => object,
+            value => panic!("expected object, got {}", value.type_name()),
+        };
+
+        let e = object.set_property("id", 2_i64).unwrap_exception();
+        assert_eq!(e.class(), "Error");
+        assert!(e.message().starts_with("Cannot modify readonly property"));
+
+        // Nothing is left pending for the next call into the engine.
+        assert_eq!(bed.eval("1 + 1").value().unwrap(), Value::Long(2));
+    });
+}
+
+#[test]
+fn write_typed_property() {
+    TestBed::run(|bed| {
+        let zval = bed.eval(DTO);
+        let object = match zval.value().unwrap() {
+            Value::Object(object) => object,
+            value => panic!("expected object, got {}", value.type_name()),
+        };
+
+        let e = object.set_property("age", "old").unwrap_exception();
+        assert_eq!(e.class(), "TypeError");
+    });
+}
+
+#[test]
+fn call_method() {
+    TestBed::run(|bed| {
+        let zval = bed.eval(DTO);
+        let object = match zval.value().unwrap() {
+            Value::Object(object) => object,
+            value => panic!("expected object, got {}", value.type_name()),
+        };
+
+        let greeting = object.call("greet", ("Hello",)).unwrap();
+        assert_eq!(greeting.extract::<String>().unwrap(), "Hello, alice!");
+        assert!(object.call("missing", ()).is_err());
+    });
+}
@@ -0,0 +1,59 @@
+#![cfg(feature = "zts")]
+
+use std::thread;
+
+use rusty_php::request::RequestInfo;
+use rusty_php::test::{lock_engine, TestSapi};
+use rusty_php::zend::Value;
+use rusty_php::PhpInit;
+
+#[test]
+fn requests_in_parallel() {
+    let _engine = lock_engine();
+    let pool = PhpInit::new(TestSapi)
+        .threads(5)
+        .init()
+        .unwrap()
+        .startup_module()
+        .unwrap()
+        .into_pool(4);
+
+    let submitters = (0..8)
+        .map(|i| {
+            let handle = pool.handle();
+            thread::spawn(move || {
+                (0..50)
+                    .map(|j| {
+                        handle.submit(
+                            RequestInfo::new().query_string(&format!("n={}", i * 100 + j)),
+                            |php| {
+                                let ret = php
+                                    .eval("isset($GLOBALS['n']) ? -1 : ($GLOBALS['n'] = (int) $_GET['n'])")
+                                    .unwrap();
+                                match ret.value().unwrap() {
+                                    Value::Long(n) => n,
+                                    v => panic!("expected an int, got: {:?}", v),
+                                }
+                            },
+                        )
+                    })
+                    .collect::<Vec<_>>()
+            })
+        })
+        .collect::<Vec<_>>();
+
+    for (i, submitter) in submitters.into_iter().enumerate() {
+        for (j, job) in submitter.join().unwrap().into_iter().enumerate() {
+            assert_eq!(job.join().unwrap(), (i * 100 + j) as i64);
+        }
+    }
+
+    let handle = pool.handle();
+    let panicked = handle.submit(RequestInfo::new(), |_| panic!("job failed"));
+    assert!(panicked.join().is_err());
+
+    pool.shutdown_all();
+
+    let late = handle.submit(RequestInfo::new(), |_| ());
+    assert!(late.join().is_err());
+}
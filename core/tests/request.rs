@@ -0,0 +1,119 @@
+use std::sync::{Arc, Mutex};
+
+use rusty_php::callback::{Callback, SapiCallback};
+use rusty_php::request::RequestInfo;
+use rusty_php::test::{lock_engine, TestSapi};
+use rusty_php::zend::Value;
+use rusty_php::PhpInit;
+
+#[test]
+fn many_sequential_requests() {
+    let _engine = lock_engine();
+    let mut module = PhpInit::new(TestSapi)
+        .init()
+        .unwrap()
+        .startup_module()
+        .unwrap();
+
+    for i in 0..1000 {
+        let php = module
+            .startup_request_with(
+                RequestInfo::new()
+                    .argv(["script.php".to_string(), i.to_string()])
+                    .query_string(&format!("n={}", i)),
+            )
+            .unwrap();
+
+        let state = php
+            .eval("implode(',', [(int) isset($GLOBALS['leak']), ob_get_level(), $_SERVER['argv'][1], $_GET['n']])")
+            .unwrap();
+        let expected = format!("0,0,{},{}", i, i);
+        assert_eq!(
+            state.value().unwrap(),
+            Value::String(expected.as_str().into())
+        );
+
+        php.eval("($GLOBALS['leak'] = true) && ob_start() && print('buffered')")
+            .unwrap();
+        module = php.shutdown();
+    }
+
+    let php = module.startup_request().unwrap();
+    let state = php.eval("($_SERVER['argc'] ?? 0) + count($_GET)").unwrap();
+    assert_eq!(state.value().unwrap(), Value::Long(0));
+    php.shutdown_all();
+}
+
+/// Keeps the output of a request, and serves its body.
+#[derive(Clone, Default)]
+struct Client {
+    output: Arc<Mutex<Vec<u8>>>,
+    body: Arc<Mutex<Vec<u8>>>,
+}
+
+impl SapiCallback for Client {
+    fn on_ub_write(&self, str: &[u8]) -> usize {
+        self.output.lock().unwrap().extend_from_slice(str);
+        str.len()
+    }
+
+    fn on_read_post(&self, buffer: &mut [u8]) -> usize {
+        let mut body = self.body.lock().unwrap();
+        let len = buffer.len().min(body.len());
+        buffer[..len].copy_from_slice(&body[..len]);
+        body.drain(..len);
+        len
+    }
+}
+
+#[test]
+fn output_of_each_request() {
+    let _engine = lock_engine();
+    let mut module = PhpInit::new(TestSapi)
+        .init()
+        .unwrap()
+        .startup_module()
+        .unwrap();
+
+    let clients = (0..3).map(|_| Client::default()).collect::<Vec<_>>();
+    for (i, client) in clients.iter().enumerate() {
+        let php = module
+            .startup_request_with(RequestInfo::new().callback(Callback::new(client.clone())))
+            .unwrap();
+        php.eval(&format!("print('request {}')", i)).unwrap();
+        module = php.shutdown();
+    }
+
+    for (i, client) in clients.iter().enumerate() {
+        assert_eq!(
+            *client.output.lock().unwrap(),
+            format!("request {}", i).into_bytes()
+        );
+    }
+
+    module.shutdown_all();
+}
+
+#[test]
+fn body_of_a_request() {
+    let _engine = lock_engine();
+    let client = Client::default();
+    client.body.lock().unwrap().extend_from_slice(b"a=1&b=2");
+
+    let php = PhpInit::new(TestSapi)
+        .init()
+        .unwrap()
+        .startup_module()
+        .unwrap()
+        .startup_request_with(
+            RequestInfo::new()
+                .method("POST")
+                .content("application/x-www-form-urlencoded", 7)
+                .callback(Callback::new(client.clone())),
+        )
+        .unwrap();
+
+    let ret = php.eval("$_POST['a'] + $_POST['b']").unwrap();
+    assert_eq!(ret.value().unwrap(), Value::Long(3));
+    php.shutdown_all();
+}
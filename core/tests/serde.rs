@@ -0,0 +1,89 @@
+#![cfg(feature = "serde")]
+
+use std::collections::BTreeMap;
+
+use rusty_php::test::TestBed;
+use rusty_php::zend::serde::{from_zval, to_zval};
+use rusty_php::zend::Value;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, PartialEq, Deserialize, Serialize)]
+struct User {
+    name: String,
+    age: u32,
+    tags: Vec<String>,
+    email: Option<String>,
+}
+
+#[derive(Debug, PartialEq, Deserialize, Serialize)]
+enum Shape {
+    Point,
+    Circle(f64),
+    Rect { w: i64, h: i64 },
+}
+
+#[test]
+fn deserialize_struct() {
+    TestBed::run(|bed| {
+        let zval = bed
+            .eval("['name' => 'alice', 'age' => 30, 'tags' => ['admin', 'dev'], 'email' => null]");
+        let user: User = from_zval(&zval).unwrap();
+        assert_eq!(
+            user,
+            User {
+                name: "alice".into(),
+                age: 30,
+                tags: vec!["admin".into(), "dev".into()],
+                email: None,
+            }
+        );
+    });
+}
+
+#[test]
+fn deserialize_error() {
+    TestBed::run(|bed| {
+        let zval = bed.eval("['name' => 'alice', 'age' => 'thirty', 'tags' => []]");
+        assert!(from_zval::<User>(&zval).is_err());
+    });
+}
+
+#[test]
+fn deserialize_int_keys() {
+    TestBed::run(|bed| {
+        let zval = bed.eval("[3 => 'c', 1 => 'a']");
+        let map: BTreeMap<String, String> = from_zval(&zval).unwrap();
+        assert_eq!(map["1"], "a");
+        assert_eq!(map["3"], "c");
+    });
+}
+
+#[test]
+fn serialize_struct() {
+    TestBed::run(|_| {
+        let user = User {
+            name: "bob".into(),
+            age: 41,
+            tags: vec!["ops".into()],
+            email: Some("bob@example.com".into()),
+        };
+        let zval = to_zval(&user).unwrap();
+
+        let array = match zval.value().unwrap() {
+            Value::Array(array) => array,
+            value => panic!("expected array, got {}", value.type_name()),
+        };
+        assert_eq!(array.len(), 4);
+        assert_eq!(from_zval::<User>(&zval).unwrap(), user);
+    });
+}
+
+#[test]
+fn enum_round_trip() {
+    TestBed::run(|_| {
+        for shape in [Shape::Point, Shape::Circle(1.5), Shape::Rect { w: 2, h: 3 }] {
+            let zval = to_zval(&shape).unwrap();
+            assert_eq!(from_zval::<Shape>(&zval).unwrap(), shape);
+        }
+    });
+}
@@ -0,0 +1,59 @@
+use rusty_php::test::TestBed;
+use rusty_php::zend::convert::IntoZval;
+use rusty_php::zend::string::ZString;
+use rusty_php::zend::Value;
+
+#[test]
+fn new_string() {
+    TestBed::run(|_| {
+        let str = ZString::new("Hello, world!");
+        assert_eq!(str.as_bytes(), b"Hello, world!");
+        assert_eq!(str.len(), 13);
+        assert!(!str.is_interned());
+        assert_eq!(str.ref_count(), Some(1));
+
+        let copy = str.clone();
+        assert_eq!(str.ref_count(), Some(2));
+        drop(copy);
+        assert_eq!(str.ref_count(), Some(1));
+    });
+}
+
+#[test]
+fn intern() {
+    TestBed::run(|_| {
+        let a = ZString::new("rusty_php_interned").intern();
+        let b = ZString::new("rusty_php_interned").intern();
+
+        assert!(a.is_interned());
+        assert_eq!(a.ref_count(), None);
+        assert_eq!(a.as_ptr(), b.as_ptr());
+    });
+}
+
+#[test]
+fn hash_matches_array_lookup() {
+    TestBed::run(|bed| {
+        let str = ZString::new("key");
+        assert_ne!(str.hash(), 0);
+        assert_eq!(str.hash(), ZString::new("key").hash());
+
+        let zval = bed.eval("['key' => 1]");
+        let array = match zval.value().unwrap() {
+            Value::Array(array) => array,
+            value => panic!("expected array, got {}", value.type_name()),
+        };
+        assert_eq!(array.get(&str).unwrap(), Some(Value::Long(1)));
+    });
+}
+
+#[test]
+fn into_zval() {
+    TestBed::run(|_| {
+        let str = ZString::new("value");
+        let zval = str.clone().into_zval();
+
+        assert_eq!(zval.value().unwrap(), Value::String("value".into()));
+        assert_eq!(str.ref_count(), Some(2));
+    });
+}
@@ -3,13 +3,37 @@ extern crate core;
 use rusty_php::test::TestBed;
 use rusty_php::zend::Value;
 
+#[test]
+fn null() {
+    TestBed::run(|bed| {
+        let zval = bed.eval("null");
+        let value = zval.value().unwrap();
+        assert_eq!(value, Value::Null);
+    });
+}
+
+#[test]
+fn bool() {
+    TestBed::run(|bed| {
+        let zval = bed.eval("1 === 1");
+        let value = zval.value().unwrap();
+        assert_eq!(value, Value::Bool(true));
+
+        let zval = bed.eval("false");
+        let value = zval.value().unwrap();
+        assert_eq!(value, Value::Bool(false));
+    });
+}
+
 #[test]
 fn long() {
     TestBed::run(|bed| {
-        let value = Value::from(bed.eval("1234500000 + 67890"));
+        let zval = bed.eval("1234500000 + 67890");
+        let value = zval.value().unwrap();
         assert_eq!(value, Value::Long(1234567890));
 
-        let value = Value::from(bed.eval("\\PHP_INT_MAX"));
+        let zval = bed.eval("\\PHP_INT_MAX");
+        let value = zval.value().unwrap();
         assert_eq!(value, Value::Long(i64::MAX));
     });
 }
@@ -17,7 +41,8 @@ fn long() {
 #[test]
 fn double() {
     TestBed::run(|bed| {
-        let value = Value::from(bed.eval("1.234 + 5.678"));
+        let zval = bed.eval("1.234 + 5.678");
+        let value = zval.value().unwrap();
         assert_eq!(value, Value::Double(6.912));
     });
 }
@@ -25,7 +50,8 @@ fn double() {
 #[test]
 fn string() {
     TestBed::run(|bed| {
-        let value = Value::from(bed.eval("'Hello, world!'"));
+        let zval = bed.eval("'Hello, world!'");
+        let value = zval.value().unwrap();
         assert_eq!(value, Value::String("Hello, world!".into()));
     });
 }
@@ -33,7 +59,8 @@ fn string() {
 #[test]
 fn array() {
     TestBed::run(|bed| {
-        let value = Value::from(bed.eval("[123, 4.56, 'Hello']"));
+        let zval = bed.eval("[123, 4.56, 'Hello']");
+        let value = zval.value().unwrap();
         let array = match value {
             Value::Array(a) => a,
             _ => panic!("not an array"),
@@ -45,7 +72,48 @@ fn array() {
                 Value::Double(4.56),
                 Value::String("Hello".into()),
             ],
-            array.into_iter().map(|e| (e.value())).collect::<Vec<_>>(),
+            array
+                .into_iter()
+                .map(|e| e.value().unwrap())
+                .collect::<Vec<_>>(),
+        );
+    });
+}
+
+#[test]
+fn object() {
+    TestBed::run(|bed| {
+        let zval = bed.eval("new \\stdClass()");
+        let value = zval.value().unwrap();
+        assert!(matches!(value, Value::Object(_)));
+    });
+}
+
+#[test]
+fn resource() {
+    TestBed::run(|bed| {
+        let zval = bed.eval("fopen('php://memory', 'r')");
+        let value = zval.value().unwrap();
+        assert!(matches!(value, Value::Resource(_)));
+    });
+}
+
+#[test]
+fn reference() {
+    TestBed::run(|bed| {
+        let zval = bed.eval("(function () { $a = 42; return [&$a]; })()");
+        let value = zval.value().unwrap();
+        let array = match value {
+            Value::Array(a) => a,
+            _ => panic!("not an array"),
+        };
+
+        assert_eq!(
+            vec![Value::Long(42)],
+            array
+                .into_iter()
+                .map(|e| e.value().unwrap())
+                .collect::<Vec<_>>(),
         );
     });
 }
@@ -0,0 +1,26 @@
+use rusty_php::test::TestBed;
+use rusty_php::zend::Value;
+
+#[test]
+fn clone_adds_reference() {
+    TestBed::run(|bed| {
+        let a = bed.eval("str_repeat('a', 3)");
+        assert_eq!(a.ref_count(), Some(1));
+
+        let b = a.clone();
+        assert_eq!(a.ref_count(), Some(2));
+        assert_eq!(b.value().unwrap(), Value::String("aaa".into()));
+
+        drop(b);
+        assert_eq!(a.ref_count(), Some(1));
+    });
+}
+
+#[test]
+fn scalars_are_not_refcounted() {
+    TestBed::run(|bed| {
+        let value = bed.eval("42");
+        assert_eq!(value.ref_count(), None);
+        assert_eq!(value.clone().value().unwrap(), Value::Long(42));
+    });
+}
@@ -0,0 +1,132 @@
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::meta::ParseNestedMeta;
+use syn::parse::Parser;
+use syn::{Error, ImplItem, ItemImpl, ItemStruct, LitStr, Result, Visibility};
+
+use crate::signature::{params, receiver, wrap_return, Receiver};
+
+pub(crate) fn expand_class(attr: TokenStream, item: TokenStream) -> Result<TokenStream> {
+    let mut name = None::<LitStr>;
+    let mut extends = None::<LitStr>;
+    let mut implements = Vec::<LitStr>::new();
+    let parser = syn::meta::parser(|meta: ParseNestedMeta| {
+        if meta.path.is_ident("name") {
+            name = Some(meta.value()?.parse()?);
+        } else if meta.path.is_ident("extends") {
+            extends = Some(meta.value()?.parse()?);
+        } else if meta.path.is_ident("implements") {
+            implements.push(meta.value()?.parse()?);
+        } else {
+            return Err(meta.error("unsupported php_class argument"));
+        }
+        Ok(())
+    });
+    parser.parse2(attr)?;
+
+    let item = syn::parse2::<ItemStruct>(item)?;
+    if !item.generics.params.is_empty() {
+        return Err(Error::new_spanned(
+            &item.generics,
+            "PHP classes cannot be generic",
+        ));
+    }
+
+    let ident = &item.ident;
+    let name = name.map_or_else(|| ident.to_string(), |name| name.value());
+    let extends = extends.map(|parent| quote!(.extends(#parent)));
+
+    Ok(quote! {
+        #item
+
+        impl #ident {
+            #[doc(hidden)]
+            pub fn __php_class() -> ::rusty_php::zend::class::Class<Self> {
+                ::rusty_php::zend::class::Class::new(#name)
+                    #extends
+                    #(.implements(#implements))*
+            }
+        }
+    })
+}
+
+pub(crate) fn expand_methods(attr: TokenStream, item: TokenStream) -> Result<TokenStream> {
+    if !attr.is_empty() {
+        return Err(Error::new_spanned(attr, "php_methods takes no arguments"));
+    }
+
+    let item = syn::parse2::<ItemImpl>(item)?;
+    if item.trait_.is_some() || !item.generics.params.is_empty() {
+        return Err(Error::new_spanned(
+            &item.self_ty,
+            "php_methods applies to inherent impls of non-generic types",
+        ));
+    }
+    let self_ty = &item.self_ty;
+
+    let mut members = Vec::new();
+    for impl_item in &item.items {
+        match impl_item {
+            ImplItem::Const(constant) if matches!(constant.vis, Visibility::Public(_)) => {
+                let ident = &constant.ident;
+                let name = ident.to_string();
+                members.push(quote!(.constant(#name, Self::#ident)));
+            }
+            ImplItem::Fn(method) if matches!(method.vis, Visibility::Public(_)) => {
+                let sig = &method.sig;
+                let ident = &sig.ident;
+                let name = ident.to_string();
+
+                let params = params(sig)?;
+                let bindings = &params.bindings;
+                let arg_infos = &params.arg_infos;
+                let call_args = &params.call_args;
+
+                let method = match receiver(sig)? {
+                    Receiver::None => {
+                        let call = wrap_return(sig, quote!(Self::#ident(#(#call_args),*)));
+                        quote! {
+                            ::rusty_php::zend::class::Method::new_static(#name, |args| {
+                                #(#bindings)*
+                                #call
+                            })
+                        }
+                    }
+                    Receiver::Ref => {
+                        let call = wrap_return(sig, quote!(this.#ident(#(#call_args),*)));
+                        quote! {
+                            ::rusty_php::zend::class::Method::new_ref(#name, |this: &Self, args| {
+                                #(#bindings)*
+                                #call
+                            })
+                        }
+                    }
+                    Receiver::Mut => {
+                        let call = wrap_return(sig, quote!(this.#ident(#(#call_args),*)));
+                        quote! {
+                            ::rusty_php::zend::class::Method::new(#name, |this: &mut Self, args| {
+                                #(#bindings)*
+                                #call
+                            })
+                        }
+                    }
+                };
+                members.push(quote!(.method(#method #(.arg(#arg_infos))*)));
+            }
+            _ => {}
+        }
+    }
+
+    Ok(quote! {
+        #item
+
+        impl #self_ty {
+            #[doc(hidden)]
+            pub fn __php_methods(
+                class: ::rusty_php::zend::class::Class<Self>,
+            ) -> ::rusty_php::zend::class::Class<Self> {
+                class #(#members)*
+            }
+        }
+    })
+}
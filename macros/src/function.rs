@@ -0,0 +1,51 @@
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote};
+use syn::meta::ParseNestedMeta;
+use syn::parse::Parser;
+use syn::{ItemFn, LitStr, Result};
+
+use crate::signature::{params, wrap_return};
+
+/// The name of the hidden function building the `Function` for `ident`.
+pub(crate) fn builder_ident(ident: &syn::Ident) -> syn::Ident {
+    format_ident!("__php_function_{}", ident)
+}
+
+pub(crate) fn expand(attr: TokenStream, item: TokenStream) -> Result<TokenStream> {
+    let mut name = None::<LitStr>;
+    let parser = syn::meta::parser(|meta: ParseNestedMeta| {
+        if meta.path.is_ident("name") {
+            name = Some(meta.value()?.parse()?);
+            Ok(())
+        } else {
+            Err(meta.error("unsupported php_function argument"))
+        }
+    });
+    parser.parse2(attr)?;
+
+    let function = syn::parse2::<ItemFn>(item)?;
+    let sig = &function.sig;
+    let ident = &sig.ident;
+    let vis = &function.vis;
+    let name = name.map_or_else(|| ident.to_string(), |name| name.value());
+    let builder = builder_ident(ident);
+
+    let params = params(sig)?;
+    let bindings = &params.bindings;
+    let arg_infos = &params.arg_infos;
+    let call_args = &params.call_args;
+    let call = wrap_return(sig, quote!(#ident(#(#call_args),*)));
+
+    Ok(quote! {
+        #function
+
+        #[doc(hidden)]
+        #vis fn #builder() -> ::rusty_php::zend::function::Function {
+            ::rusty_php::zend::function::Function::new(#name, |args| {
+                #(#bindings)*
+                #call
+            })
+            #(.arg(#arg_infos))*
+        }
+    })
+}
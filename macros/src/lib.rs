@@ -0,0 +1,78 @@
+//! Attributes declaring PHP functions, classes and modules in Rust.
+//!
+//! ```ignore
+//! #[php_module(name = "demo", version = "0.1.0")]
+//! mod demo {
+//!     use rusty_php::{php_class, php_function, php_methods};
+//!
+//!     #[php_function]
+//!     pub fn add(a: i64, b: i64) -> i64 {
+//!         a + b
+//!     }
+//!
+//!     #[php_class(implements = "Countable")]
+//!     #[derive(Default)]
+//!     pub struct Counter {
+//!         count: i64,
+//!     }
+//!
+//!     #[php_methods]
+//!     impl Counter {
+//!         pub fn count(&self) -> i64 {
+//!             self.count
+//!         }
+//!     }
+//! }
+//!
+//! let init = demo::register(PhpInit::new(sapi));
+//! ```
+
+mod class;
+mod function;
+mod module;
+mod signature;
+
+use proc_macro::TokenStream;
+
+/// Exposes a function to PHP. Parameters are converted with `FromZval` and the
+/// return value with `IntoZval`; return a `rusty_php::Result` to fail, which
+/// throws an `Error` unless an exception was thrown already. Trailing `Option`
+/// parameters are optional.
+///
+/// Use `#[php_function(name = "...")]` to choose the PHP name.
+#[proc_macro_attribute]
+pub fn php_function(attr: TokenStream, item: TokenStream) -> TokenStream {
+    function::expand(attr.into(), item.into())
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+/// Exposes a struct implementing `Default` to PHP as a class whose objects
+/// carry it. Accepts `name`, `extends` and `implements` arguments.
+#[proc_macro_attribute]
+pub fn php_class(attr: TokenStream, item: TokenStream) -> TokenStream {
+    class::expand_class(attr.into(), item.into())
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+/// Exposes the `pub` methods and constants of an impl block of a
+/// `#[php_class]` struct. Methods without a receiver become static methods.
+/// Methods taking `&mut self` borrow the object for the whole call, so calling
+/// back into a method of the same object from one throws an `Error`.
+#[proc_macro_attribute]
+pub fn php_methods(attr: TokenStream, item: TokenStream) -> TokenStream {
+    class::expand_methods(attr.into(), item.into())
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+/// Collects the functions and classes declared in an inline module into a
+/// `register` function taking and returning a `PhpInit`. With `name` and
+/// `version`, the functions are registered as an extension of that name.
+#[proc_macro_attribute]
+pub fn php_module(attr: TokenStream, item: TokenStream) -> TokenStream {
+    module::expand(attr.into(), item.into())
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
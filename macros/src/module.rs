@@ -0,0 +1,117 @@
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::meta::ParseNestedMeta;
+use syn::parse::Parser;
+use syn::{Attribute, Error, Item, ItemMod, LitStr, Result, Type};
+
+use crate::function::builder_ident;
+
+fn has_attribute(attrs: &[Attribute], name: &str) -> bool {
+    attrs.iter().any(|attr| {
+        attr.path()
+            .segments
+            .last()
+            .is_some_and(|segment| segment.ident == name)
+    })
+}
+
+pub(crate) fn expand(attr: TokenStream, item: TokenStream) -> Result<TokenStream> {
+    let mut name = None::<LitStr>;
+    let mut version = None::<LitStr>;
+    let parser = syn::meta::parser(|meta: ParseNestedMeta| {
+        if meta.path.is_ident("name") {
+            name = Some(meta.value()?.parse()?);
+        } else if meta.path.is_ident("version") {
+            version = Some(meta.value()?.parse()?);
+        } else {
+            return Err(meta.error("unsupported php_module argument"));
+        }
+        Ok(())
+    });
+    parser.parse2(attr)?;
+
+    let mut module = syn::parse2::<ItemMod>(item)?;
+    let Some((_, items)) = &mut module.content else {
+        return Err(Error::new_spanned(
+            &module,
+            "php_module applies to inline modules",
+        ));
+    };
+
+    let mut functions = Vec::new();
+    let mut classes = Vec::new();
+    let mut with_methods = Vec::new();
+    for item in items.iter() {
+        match item {
+            Item::Fn(function) if has_attribute(&function.attrs, "php_function") => {
+                let builder = builder_ident(&function.sig.ident);
+                functions.push(quote!(#builder()));
+            }
+            Item::Struct(item) if has_attribute(&item.attrs, "php_class") => {
+                classes.push(item.ident.clone());
+            }
+            Item::Impl(item) if has_attribute(&item.attrs, "php_methods") => {
+                if let Type::Path(path) = &*item.self_ty {
+                    if let Some(segment) = path.path.segments.last() {
+                        with_methods.push(segment.ident.clone());
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    let classes = classes
+        .iter()
+        .map(|ident| match with_methods.contains(ident) {
+            true => quote!(.class(#ident::__php_methods(#ident::__php_class()))),
+            _ => quote!(.class(#ident::__php_class())),
+        });
+
+    let generated = match (name, version) {
+        (Some(name), Some(version)) => quote! {
+            #[doc(hidden)]
+            pub struct __PhpExtension;
+
+            impl ::rusty_php::extension::Extension for __PhpExtension {
+                fn name(&self) -> &str {
+                    #name
+                }
+
+                fn version(&self) -> &str {
+                    #version
+                }
+
+                fn functions(&self) -> ::std::vec::Vec<::rusty_php::zend::function::Function> {
+                    ::std::vec![#(#functions),*]
+                }
+            }
+
+            /// Registers the extension and the classes of this module.
+            pub fn register<S>(init: ::rusty_php::PhpInit<S>) -> ::rusty_php::PhpInit<S>
+            where
+                S: ::rusty_php::sapi::Sapi,
+            {
+                init.extension(__PhpExtension) #(#classes)*
+            }
+        },
+        (None, None) => quote! {
+            /// Registers the functions and classes of this module.
+            pub fn register<S>(init: ::rusty_php::PhpInit<S>) -> ::rusty_php::PhpInit<S>
+            where
+                S: ::rusty_php::sapi::Sapi,
+            {
+                init #(.function(#functions))* #(#classes)*
+            }
+        },
+        _ => {
+            return Err(Error::new_spanned(
+                &module.ident,
+                "php_module takes both a name and a version, or neither",
+            ))
+        }
+    };
+
+    items.push(Item::Verbatim(generated));
+    Ok(quote!(#module))
+}
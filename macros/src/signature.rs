@@ -0,0 +1,171 @@
+use proc_macro2::{Span, TokenStream};
+use quote::{format_ident, quote};
+use syn::spanned::Spanned;
+use syn::{
+    Error, FnArg, GenericArgument, Ident, Pat, PathArguments, Result, ReturnType, Signature, Type,
+};
+
+/// The parameters of a Rust function as seen from PHP.
+pub(crate) struct Params {
+    /// Statements binding the converted arguments, one per parameter.
+    pub(crate) bindings: Vec<TokenStream>,
+    /// The expressions passed to the Rust function, in order.
+    pub(crate) call_args: Vec<TokenStream>,
+    /// `Arg` builders for reflection.
+    pub(crate) arg_infos: Vec<TokenStream>,
+}
+
+/// The receiver of a method.
+#[derive(Copy, Clone, PartialEq, Eq)]
+pub(crate) enum Receiver {
+    None,
+    Ref,
+    Mut,
+}
+
+fn last_segment(ty: &Type) -> Option<&syn::PathSegment> {
+    match ty {
+        Type::Path(path) if path.qself.is_none() => path.path.segments.last(),
+        _ => None,
+    }
+}
+
+fn inner_type(ty: &Type, wrapper: &str) -> Option<Type> {
+    let segment = last_segment(ty).filter(|s| s.ident == wrapper)?;
+    match &segment.arguments {
+        PathArguments::AngleBracketed(args) => args.args.iter().find_map(|arg| match arg {
+            GenericArgument::Type(ty) => Some(ty.clone()),
+            _ => None,
+        }),
+        _ => None,
+    }
+}
+
+fn is_arguments(ty: &Type) -> bool {
+    match ty {
+        Type::Reference(reference) => {
+            last_segment(&reference.elem).is_some_and(|s| s.ident == "Arguments")
+        }
+        _ => false,
+    }
+}
+
+/// The PHP type declared for a Rust type, if it maps to one.
+fn arg_type(ty: &Type) -> Option<TokenStream> {
+    let name = match ty {
+        Type::Reference(reference) => match &*reference.elem {
+            Type::Path(_) => last_segment(&reference.elem)?.ident.to_string(),
+            Type::Slice(_) => "str".to_string(),
+            _ => return None,
+        },
+        _ => last_segment(ty)?.ident.to_string(),
+    };
+
+    let variant = match name.as_str() {
+        "i8" | "i16" | "i32" | "i64" | "isize" | "u16" | "u32" | "u64" | "usize" => "Long",
+        "f32" | "f64" => "Double",
+        "bool" => "Bool",
+        "str" | "String" | "ZStr" | "ZString" => "String",
+        "Vec" | "HashMap" | "BTreeMap" | "ZArray" => "Array",
+        "ZObject" => "Object",
+        _ => return None,
+    };
+    let variant = Ident::new(variant, Span::call_site());
+    Some(quote!(::rusty_php::zend::function::ArgType::#variant))
+}
+
+/// Whether the function returns a `Result` that is passed on as is.
+pub(crate) fn returns_result(sig: &Signature) -> bool {
+    match &sig.output {
+        ReturnType::Type(_, ty) => last_segment(ty).is_some_and(|s| s.ident == "Result"),
+        _ => false,
+    }
+}
+
+/// Wraps a call to the Rust function into the `Result` the handler returns.
+pub(crate) fn wrap_return(sig: &Signature, call: TokenStream) -> TokenStream {
+    match returns_result(sig) {
+        true => call,
+        _ => quote!(::rusty_php::Ok(#call)),
+    }
+}
+
+pub(crate) fn receiver(sig: &Signature) -> Result<Receiver> {
+    match sig.receiver() {
+        None => Ok(Receiver::None),
+        Some(receiver) if receiver.reference.is_none() => Err(Error::new(
+            receiver.span(),
+            "PHP methods must take `&self` or `&mut self`",
+        )),
+        Some(receiver) if receiver.mutability.is_some() => Ok(Receiver::Mut),
+        Some(_) => Ok(Receiver::Ref),
+    }
+}
+
+pub(crate) fn params(sig: &Signature) -> Result<Params> {
+    if !sig.generics.params.is_empty() {
+        return Err(Error::new(
+            sig.generics.span(),
+            "PHP functions cannot be generic",
+        ));
+    }
+
+    let typed = sig
+        .inputs
+        .iter()
+        .filter_map(|input| match input {
+            FnArg::Typed(typed) => Some(typed),
+            _ => None,
+        })
+        .collect::<Vec<_>>();
+
+    // Trailing `Option` parameters may be left out by the caller.
+    let optional_from = typed
+        .iter()
+        .rposition(|typed| is_arguments(&typed.ty) || inner_type(&typed.ty, "Option").is_none())
+        .map_or(0, |position| position + 1);
+
+    let mut params = Params {
+        bindings: Vec::new(),
+        call_args: Vec::new(),
+        arg_infos: Vec::new(),
+    };
+
+    let mut index = 0_usize;
+    for (position, typed) in typed.iter().enumerate() {
+        if is_arguments(&typed.ty) {
+            params.call_args.push(quote!(args));
+            continue;
+        }
+
+        let name = match &*typed.pat {
+            Pat::Ident(pat) => pat.ident.to_string(),
+            _ => format!("arg{}", index),
+        };
+        let name = name.trim_start_matches("r#").to_string();
+        let binding = format_ident!("__arg{}", index);
+        let ty = &typed.ty;
+
+        params
+            .bindings
+            .push(quote!(let #binding: #ty = args.get(#index)?;));
+        params.call_args.push(quote!(#binding));
+
+        let option = inner_type(ty, "Option");
+        let mut arg_info = quote!(::rusty_php::zend::function::Arg::new(#name));
+        if let Some(arg_type) = arg_type(option.as_ref().unwrap_or(ty)) {
+            arg_info = quote!(#arg_info.ty(#arg_type));
+        }
+        if option.is_some() {
+            arg_info = quote!(#arg_info.nullable());
+        }
+        if position >= optional_from {
+            arg_info = quote!(#arg_info.default("null"));
+        }
+        params.arg_infos.push(arg_info);
+
+        index += 1;
+    }
+
+    Ok(params)
+}
@@ -1,4 +1,9 @@
 fn main() {
+    println!("cargo:rerun-if-changed=src/zend/bailout.c");
+    cc::Build::new()
+        .file("src/zend/bailout.c")
+        .compile("rusty_php_bailout");
+
     println!("cargo:rustc-link-search=/usr/local/lib");
     println!("cargo:rustc-link-search=/usr/lib");
     println!("cargo:rustc-link-lib=dylib=php");
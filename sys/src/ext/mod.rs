@@ -1 +1 @@
-pub(crate) mod standard;
+pub mod standard;
@@ -1,6 +1,6 @@
 #![allow(unused)]
 
-use std::ffi::c_uint;
+use std::ffi::{c_char, c_int, c_uint};
 
 pub const PHP_INFO_GENERAL: c_uint = 1 << 0;
 pub const PHP_INFO_CREDITS: c_uint = 1 << 1;
@@ -13,4 +13,10 @@ pub const PHP_INFO_ALL: c_uint = 0xFFFFFFFF;
 
 extern "C" {
     pub fn php_print_info(flags: c_uint);
+
+    pub fn php_info_print_table_start();
+    pub fn php_info_print_table_end();
+    pub fn php_info_print_table_header(num_cols: c_int, ...);
+    pub fn php_info_print_table_row(num_cols: c_int, ...);
+    pub fn php_info_print_table_colspan_header(num_cols: c_int, header: *const c_char);
 }
@@ -1 +1 @@
-pub(crate) mod info;
+pub mod info;
@@ -1,9 +1,11 @@
 #![feature(c_variadic)]
 #![allow(improper_ctypes)]
+#![allow(clippy::missing_safety_doc)]
 
 use std::ffi::c_void;
 
 use crate::sapi::SapiModuleStruct;
+use crate::zend::modules::ZendModuleEntry;
 use crate::zend::stream::ZendFileHandle;
 use crate::zend::ZendResult;
 
@@ -16,14 +18,19 @@ pub mod zend;
 pub mod tsrm;
 
 extern "C" {
+    /// Registers the extensions compiled into PHP, called by
+    /// `php_module_startup` before the modules are started.
+    pub static mut php_register_internal_extensions_func: unsafe extern "C" fn() -> ZendResult;
+    pub fn php_register_internal_extensions() -> ZendResult;
+
     pub fn php_module_startup(
         sf: *mut SapiModuleStruct,
-        additional_module: *mut c_void,
+        additional_module: *mut ZendModuleEntry,
     ) -> ZendResult;
     pub fn php_module_shutdown() -> ZendResult;
     pub fn php_request_startup() -> ZendResult;
     pub fn php_request_shutdown(dummy: *mut c_void) -> ZendResult;
-    pub fn php_execute_script(primary_file: *mut ZendFileHandle);
+    pub fn php_execute_script(primary_file: *mut ZendFileHandle) -> bool;
     pub fn sapi_startup(sf: *mut SapiModuleStruct);
     pub fn sapi_shutdown();
 
@@ -31,5 +38,5 @@ extern "C" {
     pub fn php_tsrm_startup() -> bool;
 
     #[cfg(feature = "zts")]
-    pub fn php_tsrm_startup_ex(expected_threads: isize) -> bool;
+    pub fn php_tsrm_startup_ex(expected_threads: std::ffi::c_int) -> bool;
 }
@@ -3,6 +3,7 @@ use std::ffi::{c_char, c_double, c_int, c_uchar, c_uint, c_void};
 use libc::{gid_t, uid_t};
 
 use crate::streams::PhpStream;
+use crate::zend::api::ZendFcallInfoCache;
 use crate::zend::*;
 
 pub const SAPI_HEADER_SENT_SUCCESSFULLY: c_int = 1;
@@ -92,7 +93,7 @@ pub struct SapiGlobalsStruct {
     pub global_request_time: c_double,
     pub known_post_content_types: HashTable,
     pub callback_func: Zval,
-    // TODO: pub zend_fcall_info_cache: ZendFcallInfoCache
+    pub fci_cache: ZendFcallInfoCache,
 }
 
 #[repr(C)]
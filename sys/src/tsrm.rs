@@ -4,13 +4,15 @@ use std::ptr::null_mut;
 pub type TsRsrcId = c_int;
 
 extern "C" {
-    pub fn ts_resource_ex(id: TsRsrcId, th_id: *mut c_void); // TODO: THREAD_T
+    pub fn ts_resource_ex(id: TsRsrcId, th_id: *mut c_void) -> *mut c_void; // TODO: THREAD_T
+    pub fn ts_free_thread();
+    pub fn tsrm_shutdown();
 
     pub fn tsrm_get_ls_cache() -> *mut c_void;
 }
 
 #[inline]
-pub unsafe fn ts_resource(id: TsRsrcId) {
+pub unsafe fn ts_resource(id: TsRsrcId) -> *mut c_void {
     ts_resource_ex(id, null_mut())
 }
 
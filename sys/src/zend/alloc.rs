@@ -0,0 +1,71 @@
+use std::ffi::c_void;
+#[cfg(feature = "zend_debug")]
+use std::ffi::{c_char, c_uint};
+
+#[cfg(not(feature = "zend_debug"))]
+extern "C" {
+    pub fn _emalloc(size: usize) -> *mut c_void;
+    pub fn _efree(ptr: *mut c_void);
+}
+
+#[cfg(feature = "zend_debug")]
+extern "C" {
+    pub fn _emalloc(
+        size: usize,
+        filename: *const c_char,
+        lineno: c_uint,
+        orig_filename: *const c_char,
+        orig_lineno: c_uint,
+    ) -> *mut c_void;
+    pub fn _efree(
+        ptr: *mut c_void,
+        filename: *const c_char,
+        lineno: c_uint,
+        orig_filename: *const c_char,
+        orig_lineno: c_uint,
+    );
+}
+
+#[cfg(feature = "zend_debug")]
+const FILENAME: *const c_char = concat!(file!(), "\0").as_ptr() as *const c_char;
+
+pub const ZEND_MM_ALIGNMENT: usize = 8;
+
+#[inline]
+pub const fn zend_mm_aligned_size(size: usize) -> usize {
+    (size + ZEND_MM_ALIGNMENT - 1) & !(ZEND_MM_ALIGNMENT - 1)
+}
+
+#[inline]
+pub unsafe fn emalloc(size: usize) -> *mut c_void {
+    #[cfg(not(feature = "zend_debug"))]
+    return _emalloc(size);
+
+    #[cfg(feature = "zend_debug")]
+    return _emalloc(size, FILENAME, line!(), std::ptr::null(), 0);
+}
+
+#[inline]
+pub unsafe fn efree(ptr: *mut c_void) {
+    #[cfg(not(feature = "zend_debug"))]
+    return _efree(ptr);
+
+    #[cfg(feature = "zend_debug")]
+    return _efree(ptr, FILENAME, line!(), std::ptr::null(), 0);
+}
+
+#[inline]
+pub unsafe fn pemalloc(size: usize, persistent: bool) -> *mut c_void {
+    match persistent {
+        true => libc::malloc(size),
+        _ => emalloc(size),
+    }
+}
+
+#[inline]
+pub unsafe fn pefree(ptr: *mut c_void, persistent: bool) {
+    match persistent {
+        true => libc::free(ptr),
+        _ => efree(ptr),
+    }
+}
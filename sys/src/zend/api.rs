@@ -0,0 +1,96 @@
+use std::ffi::{c_char, c_int, c_void};
+
+use crate::zend::modules::ZendModuleEntry;
+use crate::zend::{
+    HashTable, ZendClassEntry, ZendFunction, ZendLong, ZendObject, ZendResult, ZendString, Zval,
+};
+
+pub type ZendClassConstant = c_void; // TODO: zend_class_constant
+
+pub const ZEND_FETCH_CLASS_NO_AUTOLOAD: u32 = 0x80;
+
+#[repr(C)]
+#[derive(Debug)]
+pub struct ZendFcallInfo {
+    pub size: usize,
+    pub function_name: Zval,
+    pub retval: *mut Zval,
+    pub params: *mut Zval,
+    pub object: *mut ZendObject,
+    pub param_count: u32,
+    pub named_params: *mut HashTable,
+}
+
+#[repr(C)]
+#[derive(Debug)]
+pub struct ZendFcallInfoCache {
+    pub function_handler: *mut ZendFunction,
+    pub calling_scope: *mut ZendClassEntry,
+    pub called_scope: *mut ZendClassEntry,
+    pub object: *mut ZendObject,
+}
+
+extern "C" {
+    pub fn zend_call_function(
+        fci: *mut ZendFcallInfo,
+        fci_cache: *mut ZendFcallInfoCache,
+    ) -> ZendResult;
+    pub fn zend_is_callable_ex(
+        callable: *mut Zval,
+        object: *mut ZendObject,
+        check_flags: u32,
+        callable_name: *mut *mut ZendString,
+        fcc: *mut ZendFcallInfoCache,
+        error: *mut *mut c_char,
+    ) -> bool;
+
+    pub fn zend_call_known_function(
+        func: *mut ZendFunction,
+        object: *mut ZendObject,
+        called_scope: *mut ZendClassEntry,
+        retval_ptr: *mut Zval,
+        param_count: u32,
+        params: *mut Zval,
+        named_params: *mut HashTable,
+    );
+
+    pub fn zend_lookup_class_ex(
+        name: *mut ZendString,
+        lcname: *mut ZendString,
+        flags: u32,
+    ) -> *mut ZendClassEntry;
+
+    pub fn zend_register_internal_class_ex(
+        class_entry: *mut ZendClassEntry,
+        parent_ce: *mut ZendClassEntry,
+    ) -> *mut ZendClassEntry;
+    pub fn zend_class_implements(class_entry: *mut ZendClassEntry, num_interfaces: c_int, ...);
+
+    pub fn zend_declare_class_constant_ex(
+        ce: *mut ZendClassEntry,
+        name: *mut ZendString,
+        value: *mut Zval,
+        flags: c_int,
+        doc_comment: *mut ZendString,
+    ) -> *mut ZendClassConstant;
+    pub fn zend_declare_property_ex(
+        ce: *mut ZendClassEntry,
+        name: *mut ZendString,
+        property: *mut Zval,
+        access_type: c_int,
+        doc_comment: *mut ZendString,
+    );
+
+    pub fn zend_register_internal_module(module: *mut ZendModuleEntry) -> *mut ZendModuleEntry;
+
+    pub fn object_properties_init(object: *mut ZendObject, class_type: *mut ZendClassEntry);
+
+    pub fn zend_parse_arg_bool_weak(arg: *const Zval, dest: *mut bool, arg_num: u32) -> bool;
+    pub fn zend_parse_arg_long_weak(arg: *const Zval, dest: *mut ZendLong, arg_num: u32) -> bool;
+    pub fn zend_parse_arg_double_weak(arg: *const Zval, dest: *mut f64, arg_num: u32) -> bool;
+    pub fn zend_parse_arg_str_weak(
+        arg: *mut Zval,
+        dest: *mut *mut ZendString,
+        arg_num: u32,
+    ) -> bool;
+}
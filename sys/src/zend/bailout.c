@@ -0,0 +1,26 @@
+/*
+ * zend_try / zend_catch for Rust, which cannot call setjmp itself.
+ *
+ * Mirrors the macros in Zend/zend.h. PHP builds use sigsetjmp wherever it is
+ * available, so the jump buffer type must match `JMP_BUF` there.
+ */
+
+#include <setjmp.h>
+#include <stdbool.h>
+
+bool rusty_php_try(sigjmp_buf **bailout, void (*callback)(void *), void *data)
+{
+    sigjmp_buf *orig_bailout = *bailout;
+    sigjmp_buf jmp;
+    bool bailed_out = false;
+
+    *bailout = &jmp;
+    if (sigsetjmp(jmp, 0) == 0) {
+        callback(data);
+    } else {
+        bailed_out = true;
+    }
+    *bailout = orig_bailout;
+
+    return bailed_out;
+}
@@ -0,0 +1,46 @@
+use std::ffi::{c_char, c_void};
+use std::ptr::addr_of_mut;
+
+use crate::zend::globals::eg;
+
+extern "C" {
+    fn rusty_php_try(
+        bailout: *mut *mut c_void,
+        callback: unsafe extern "C" fn(*mut c_void),
+        data: *mut c_void,
+    ) -> bool;
+
+    pub fn _zend_bailout(filename: *const c_char, lineno: u32) -> !;
+}
+
+/// Runs `f` like `zend_try`, returning `None` if the engine bailed out of it
+/// after a fatal error or a timeout, in place of unwinding into the caller.
+///
+/// # Safety
+/// A bailout `longjmp`s over the frames of `f` without running destructors,
+/// so `f` should do little more than call into the engine. The engine must
+/// have been started on this thread.
+pub unsafe fn zend_try<F, R>(f: F) -> Option<R>
+where
+    F: FnOnce() -> R,
+{
+    unsafe extern "C" fn call<F, R>(data: *mut c_void)
+    where
+        F: FnOnce() -> R,
+    {
+        let (f, ret) = &mut *(data as *mut (Option<F>, Option<R>));
+        *ret = f.take().map(|f| f());
+    }
+
+    let mut data = (Some(f), None);
+    let bailed_out = rusty_php_try(
+        addr_of_mut!(eg!(bailout)),
+        call::<F, R>,
+        &mut data as *mut (Option<F>, Option<R>) as *mut c_void,
+    );
+
+    match bailed_out {
+        true => None,
+        _ => data.1,
+    }
+}
@@ -1,5 +1,91 @@
-use crate::zend::{ZendResult, ZendString};
+use std::ffi::c_void;
+use std::mem::size_of;
+
+use crate::zend::modules::ZendModuleEntry;
+use crate::zend::{
+    HashTable, ZendArray, ZendClassEntry, ZendFunction, ZendInternalArgInfo, ZendResult,
+    ZendString, ZendUchar, ZifHandler, Zval,
+};
+
+pub const ZEND_INTERNAL_FUNCTION: ZendUchar = 1;
+pub const ZEND_USER_FUNCTION: ZendUchar = 2;
+
+pub const ZEND_MAX_RESERVED_RESOURCES: usize = 6;
+
+pub const ZEND_ACC_PUBLIC: u32 = 1 << 0;
+pub const ZEND_ACC_PROTECTED: u32 = 1 << 1;
+pub const ZEND_ACC_PRIVATE: u32 = 1 << 2;
+pub const ZEND_ACC_PPP_MASK: u32 = ZEND_ACC_PUBLIC | ZEND_ACC_PROTECTED | ZEND_ACC_PRIVATE;
+pub const ZEND_ACC_CHANGED: u32 = 1 << 3;
+pub const ZEND_ACC_STATIC: u32 = 1 << 4;
+pub const ZEND_ACC_FINAL: u32 = 1 << 5;
+pub const ZEND_ACC_ABSTRACT: u32 = 1 << 6;
+pub const ZEND_ACC_READONLY: u32 = 1 << 7;
+pub const ZEND_ACC_USE_GUARDS: u32 = 1 << 11;
+pub const ZEND_ACC_STRICT_TYPES: u32 = 1 << 31;
+
+pub const ZEND_ACC_INTERFACE: u32 = 1 << 0;
+pub const ZEND_ACC_TRAIT: u32 = 1 << 1;
+pub const ZEND_ACC_IMPLICIT_ABSTRACT_CLASS: u32 = 1 << 4;
+pub const ZEND_ACC_EXPLICIT_ABSTRACT_CLASS: u32 = 1 << 6;
+pub const ZEND_ACC_ENUM: u32 = 1 << 28;
+
+#[repr(C)]
+#[derive(Debug)]
+pub struct ZendInternalFunction {
+    pub ty: ZendUchar,
+    pub arg_flags: [ZendUchar; 3],
+    pub fn_flags: u32,
+    pub function_name: *mut ZendString,
+    pub scope: *mut ZendClassEntry,
+    pub prototype: *mut ZendFunction,
+    pub num_args: u32,
+    pub required_num_args: u32,
+    pub arg_info: *mut ZendInternalArgInfo,
+    pub attributes: *mut HashTable,
+    pub t: u32,
+    pub run_time_cache_ptr: *mut c_void,
+
+    pub handler: Option<ZifHandler>,
+    pub module: *mut ZendModuleEntry,
+    pub reserved: [*mut c_void; ZEND_MAX_RESERVED_RESOURCES],
+}
+
+#[repr(C)]
+#[derive(Debug)]
+pub struct ZendExecuteData {
+    pub opline: *const c_void, // TODO: ZendOp
+    pub call: *mut ZendExecuteData,
+    pub return_value: *mut Zval,
+    pub func: *mut ZendFunction,
+    pub this: Zval,
+    pub prev_execute_data: *mut ZendExecuteData,
+    pub symbol_table: *mut ZendArray,
+    pub run_time_cache: *mut *mut c_void,
+    pub extra_named_params: *mut ZendArray,
+}
 
 extern "C" {
     pub fn zend_is_auto_global(name: *mut ZendString) -> ZendResult;
 }
+
+pub const ZEND_CALL_FRAME_SLOT: usize = size_of::<ZendExecuteData>().div_ceil(size_of::<Zval>());
+
+#[inline]
+pub unsafe fn zend_call_num_args(call: *const ZendExecuteData) -> u32 {
+    (*call).this.u2
+}
+
+/// Whether the function running `call` was declared with `strict_types=1`.
+#[inline]
+pub unsafe fn zend_call_uses_strict_types(call: *const ZendExecuteData) -> bool {
+    // Every function starts with the common fields of an internal function.
+    let func = (*call).func as *const ZendInternalFunction;
+    (*func).fn_flags & ZEND_ACC_STRICT_TYPES != 0
+}
+
+/// Returns the `n`th argument of a call, counting from 1.
+#[inline]
+pub unsafe fn zend_call_arg(call: *mut ZendExecuteData, n: usize) -> *mut Zval {
+    (call as *mut Zval).add(ZEND_CALL_FRAME_SLOT + n - 1)
+}
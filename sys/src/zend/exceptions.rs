@@ -0,0 +1,24 @@
+use std::ffi::c_char;
+
+use crate::zend::{ZendClassEntry, ZendLong, ZendObject, Zval};
+
+extern "C" {
+    pub static mut zend_ce_throwable: *mut ZendClassEntry;
+    pub static mut zend_ce_exception: *mut ZendClassEntry;
+    pub static mut zend_ce_error: *mut ZendClassEntry;
+
+    pub fn zend_clear_exception();
+
+    pub fn zend_throw_exception(
+        exception_ce: *mut ZendClassEntry,
+        message: *const c_char,
+        code: ZendLong,
+    ) -> *mut ZendObject;
+    pub fn zend_throw_exception_object(exception: *mut Zval);
+    pub fn zend_is_unwind_exit(ex: *const ZendObject) -> bool;
+    pub fn zend_exception_set_previous(exception: *mut ZendObject, add_previous: *mut ZendObject);
+
+    pub fn zend_throw_error(exception_ce: *mut ZendClassEntry, format: *const c_char, ...);
+    pub fn zend_argument_type_error(arg_num: u32, format: *const c_char, ...);
+    pub fn zend_wrong_parameters_count_error(min_num_args: u32, max_num_args: u32);
+}
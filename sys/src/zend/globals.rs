@@ -0,0 +1,136 @@
+use std::ffi::{c_int, c_void};
+
+use crate::zend::compile::ZendExecuteData;
+use crate::zend::{HashTable, ZendArray, ZendClassEntry, ZendLong, ZendObject, Zval};
+
+pub const SYMTABLE_CACHE_SIZE: usize = 32;
+
+#[repr(C)]
+#[derive(Debug)]
+pub struct ZendStack {
+    pub size: c_int,
+    pub top: c_int,
+    pub max: c_int,
+    pub elements: *mut c_void,
+}
+
+#[repr(C)]
+#[derive(Debug)]
+pub struct ZendObjectsStore {
+    pub object_buckets: *mut *mut ZendObject,
+    pub top: u32,
+    pub size: u32,
+    pub free_list_head: c_int,
+}
+
+/// The leading fields of `zend_executor_globals`, up to the exception state.
+///
+/// The struct is only ever accessed through the pointer the engine owns, so
+/// the fields past `opline_before_exception` are left out.
+#[repr(C)]
+#[derive(Debug)]
+pub struct ZendExecutorGlobals {
+    pub uninitialized_zval: Zval,
+    pub error_zval: Zval,
+
+    pub symtable_cache: [*mut ZendArray; SYMTABLE_CACHE_SIZE],
+    pub symtable_cache_limit: *mut *mut ZendArray,
+    pub symtable_cache_ptr: *mut *mut ZendArray,
+
+    pub symbol_table: ZendArray,
+    pub included_files: HashTable,
+
+    pub bailout: *mut c_void, // TODO: JMP_BUF
+
+    pub error_reporting: c_int,
+    pub exit_status: c_int,
+
+    pub function_table: *mut HashTable,
+    pub class_table: *mut HashTable,
+    pub zend_constants: *mut HashTable,
+
+    pub vm_stack_top: *mut Zval,
+    pub vm_stack_end: *mut Zval,
+    pub vm_stack: *mut c_void,
+    pub vm_stack_page_size: usize,
+
+    pub current_execute_data: *mut ZendExecuteData,
+    pub fake_scope: *mut ZendClassEntry,
+
+    pub jit_trace_num: u32,
+
+    pub precision: ZendLong,
+
+    pub ticks_count: c_int,
+
+    pub persistent_constants_count: u32,
+    pub persistent_functions_count: u32,
+    pub persistent_classes_count: u32,
+
+    pub in_autoload: *mut HashTable,
+    pub full_tables_cleanup: bool,
+
+    pub no_extensions: bool,
+
+    pub vm_interrupt: bool, // zend_atomic_bool
+    pub timed_out: bool,    // zend_atomic_bool
+    pub hard_timeout: ZendLong,
+
+    pub regular_list: HashTable,
+    pub persistent_list: HashTable,
+
+    pub user_error_handler_error_reporting: c_int,
+    pub user_error_handler: Zval,
+    pub user_exception_handler: Zval,
+    pub user_error_handlers_error_reporting: ZendStack,
+    pub user_error_handlers: ZendStack,
+    pub user_exception_handlers: ZendStack,
+
+    pub error_handling: c_int,
+    pub exception_class: *mut ZendClassEntry,
+
+    pub timeout_seconds: ZendLong,
+
+    pub capture_warnings_during_sccp: c_int,
+
+    pub ini_directives: *mut HashTable,
+    pub modified_ini_directives: *mut HashTable,
+    pub error_reporting_ini_entry: *mut c_void,
+
+    pub objects_store: ZendObjectsStore,
+    pub exception: *mut ZendObject,
+    pub prev_exception: *mut ZendObject,
+    pub opline_before_exception: *const c_void,
+}
+
+#[cfg(feature = "zts")]
+extern "C" {
+    pub static executor_globals_offset: usize;
+}
+
+#[cfg(not(feature = "zts"))]
+extern "C" {
+    pub static mut executor_globals: ZendExecutorGlobals;
+}
+
+#[cfg(feature = "zts")]
+#[macro_export]
+macro_rules! eg {
+    ($v: ident) => {
+        $crate::zend::zend_tsrmg_fast!(
+            $crate::zend::globals::executor_globals_offset,
+            *mut $crate::zend::globals::ZendExecutorGlobals,
+            $v
+        )
+    };
+}
+
+#[cfg(not(feature = "zts"))]
+#[macro_export]
+macro_rules! eg {
+    ($v: ident) => {
+        $crate::zend::globals::executor_globals.$v
+    };
+}
+
+pub use eg;
@@ -0,0 +1,102 @@
+use std::ffi::c_char;
+
+use crate::zend::{HashTable, ZendResult, ZendString, ZendUlong, Zval};
+
+extern "C" {
+    pub fn _zend_new_array(size: u32) -> *mut HashTable;
+
+    pub fn zend_hash_str_update(
+        ht: *mut HashTable,
+        key: *const c_char,
+        len: usize,
+        p_data: *mut Zval,
+    ) -> *mut Zval;
+    pub fn zend_hash_index_update(ht: *mut HashTable, h: ZendUlong, p_data: *mut Zval)
+        -> *mut Zval;
+    pub fn zend_hash_next_index_insert(ht: *mut HashTable, p_data: *mut Zval) -> *mut Zval;
+
+    pub fn zend_hash_find(ht: *const HashTable, key: *mut ZendString) -> *mut Zval;
+    pub fn zend_hash_str_find(ht: *const HashTable, key: *const c_char, len: usize) -> *mut Zval;
+    pub fn zend_hash_index_find(ht: *const HashTable, h: ZendUlong) -> *mut Zval;
+
+    pub fn zend_hash_del(ht: *mut HashTable, key: *mut ZendString) -> ZendResult;
+    pub fn zend_hash_str_del(ht: *mut HashTable, key: *const c_char, len: usize) -> ZendResult;
+    pub fn zend_hash_index_del(ht: *mut HashTable, h: ZendUlong) -> ZendResult;
+
+    pub fn zend_hash_clean(ht: *mut HashTable);
+
+    pub fn zend_array_dup(source: *mut HashTable) -> *mut HashTable;
+    pub fn zend_array_destroy(ht: *mut HashTable);
+
+    pub fn _zend_handle_numeric_str_ex(
+        key: *const c_char,
+        length: usize,
+        idx: *mut ZendUlong,
+    ) -> bool;
+}
+
+#[inline]
+pub unsafe fn zend_new_array(size: u32) -> *mut HashTable {
+    _zend_new_array(size)
+}
+
+#[inline]
+pub unsafe fn _zend_handle_numeric_str(
+    key: *const c_char,
+    length: usize,
+    idx: *mut ZendUlong,
+) -> bool {
+    if length == 0 {
+        return false;
+    }
+
+    let first = *key as u8;
+    let negative = first == b'-' && length > 1 && (*key.add(1) as u8).is_ascii_digit();
+    if !first.is_ascii_digit() && !negative {
+        return false;
+    }
+    _zend_handle_numeric_str_ex(key, length, idx)
+}
+
+#[inline]
+pub unsafe fn zend_symtable_str_update(
+    ht: *mut HashTable,
+    str: *const c_char,
+    len: usize,
+    p_data: *mut Zval,
+) -> *mut Zval {
+    let mut idx: ZendUlong = 0;
+
+    match _zend_handle_numeric_str(str, len, &mut idx) {
+        true => zend_hash_index_update(ht, idx, p_data),
+        _ => zend_hash_str_update(ht, str, len, p_data),
+    }
+}
+
+#[inline]
+pub unsafe fn zend_symtable_str_find(
+    ht: *const HashTable,
+    str: *const c_char,
+    len: usize,
+) -> *mut Zval {
+    let mut idx: ZendUlong = 0;
+
+    match _zend_handle_numeric_str(str, len, &mut idx) {
+        true => zend_hash_index_find(ht, idx),
+        _ => zend_hash_str_find(ht, str, len),
+    }
+}
+
+#[inline]
+pub unsafe fn zend_symtable_str_del(
+    ht: *mut HashTable,
+    str: *const c_char,
+    len: usize,
+) -> ZendResult {
+    let mut idx: ZendUlong = 0;
+
+    match _zend_handle_numeric_str(str, len, &mut idx) {
+        true => zend_hash_index_del(ht, idx),
+        _ => zend_hash_str_del(ht, str, len),
+    }
+}
@@ -1,13 +1,30 @@
-use std::ffi::{c_char, c_double, c_uchar, c_void};
+use std::ffi::{c_char, c_double, c_int, c_uchar, c_void};
 use std::fmt::{Debug, Formatter};
 use std::mem::ManuallyDrop;
 
 use libc::stat;
 
+use crate::zend::compile::ZendExecuteData;
+use crate::zend::modules::ZendModuleEntry;
+use crate::zend::object_handlers::ZendObjectHandlers;
+
+pub mod alloc;
+pub mod api;
+pub mod bailout;
 pub mod compile;
+pub mod errors;
+pub mod exceptions;
 pub mod execute;
+pub mod globals;
+pub mod hash;
+pub mod modules;
+pub mod object_handlers;
+pub mod objects;
+pub mod observer;
+pub mod operators;
 pub mod stream;
 pub mod string;
+pub mod type_info;
 
 pub const IS_UNDEF: u32 = 0;
 pub const IS_NULL: u32 = 1;
@@ -39,6 +56,40 @@ pub(crate) const IS_PTR: u32 = 13;
 #[allow(unused)]
 pub(crate) const IS_ALIAS_PTR: u32 = 14;
 
+pub const Z_TYPE_MASK: u32 = 0xff;
+pub const Z_TYPE_FLAGS_MASK: u32 = 0xff00;
+pub const Z_TYPE_FLAGS_SHIFT: u32 = 8;
+
+pub const IS_TYPE_REFCOUNTED: u32 = 1 << 0;
+pub const IS_TYPE_COLLECTABLE: u32 = 1 << 1;
+
+// Extended types for zval type_info
+pub const IS_INTERNED_STRING_EX: u32 = IS_STRING;
+pub const IS_STRING_EX: u32 = IS_STRING | (IS_TYPE_REFCOUNTED << Z_TYPE_FLAGS_SHIFT);
+pub const IS_ARRAY_EX: u32 =
+    IS_ARRAY | ((IS_TYPE_REFCOUNTED | IS_TYPE_COLLECTABLE) << Z_TYPE_FLAGS_SHIFT);
+pub const IS_OBJECT_EX: u32 =
+    IS_OBJECT | ((IS_TYPE_REFCOUNTED | IS_TYPE_COLLECTABLE) << Z_TYPE_FLAGS_SHIFT);
+pub const IS_RESOURCE_EX: u32 = IS_RESOURCE | (IS_TYPE_REFCOUNTED << Z_TYPE_FLAGS_SHIFT);
+pub const IS_REFERENCE_EX: u32 = IS_REFERENCE | (IS_TYPE_REFCOUNTED << Z_TYPE_FLAGS_SHIFT);
+
+pub const GC_TYPE_MASK: u32 = 0x0000000f;
+pub const GC_FLAGS_MASK: u32 = 0x000003f0;
+pub const GC_INFO_MASK: u32 = 0xfffffc00;
+pub const GC_FLAGS_SHIFT: u32 = 0;
+pub const GC_INFO_SHIFT: u32 = 10;
+
+pub const GC_NOT_COLLECTABLE: u32 = 1 << 4;
+pub const GC_PROTECTED: u32 = 1 << 5; // used for recursion detection
+pub const GC_IMMUTABLE: u32 = 1 << 6; // can't be changed in place
+pub const GC_PERSISTENT: u32 = 1 << 7; // allocated using malloc
+pub const GC_PERSISTENT_LOCAL: u32 = 1 << 8; // persistent, but thread-local
+
+pub const GC_NULL: u32 = IS_NULL | (GC_NOT_COLLECTABLE << GC_FLAGS_SHIFT);
+pub const GC_STRING: u32 = IS_STRING | (GC_NOT_COLLECTABLE << GC_FLAGS_SHIFT);
+pub const GC_ARRAY: u32 = IS_ARRAY;
+pub const GC_OBJECT: u32 = IS_OBJECT;
+
 pub const HASH_FLAG_CONSISTENCY: u32 = (1 << 0) | (1 << 1);
 pub const HASH_FLAG_PACKED: u32 = 1 << 2;
 pub const HASH_FLAG_UNINITIALIZED: u32 = 1 << 3;
@@ -59,7 +110,7 @@ pub type ZendUchar = c_uchar;
 #[cfg(feature = "zend_enable_zval_long64")]
 mod long {
     pub type ZendLong = i64;
-    pub type ZendUlong = u32;
+    pub type ZendUlong = u64;
 }
 
 #[cfg(not(feature = "zend_enable_zval_long64"))]
@@ -144,6 +195,144 @@ pub struct ZendArray {
 
 pub type HashTable = ZendArray;
 
+#[repr(C)]
+#[derive(Debug)]
+pub struct ZendObject {
+    pub gc: ZendRefCountedH,
+    pub handle: u32,
+    pub ce: *mut ZendClassEntry,
+    pub handlers: *const ZendObjectHandlers,
+    pub properties: *mut HashTable,
+    pub properties_table: [Zval; 1],
+}
+
+// TODO: ZendFunction, a union of the internal and user functions sharing the
+// common prefix of `ZendInternalFunction`.
+pub type ZendFunction = c_void;
+
+pub const ZEND_INTERNAL_CLASS: c_char = 1;
+pub const ZEND_USER_CLASS: c_char = 2;
+
+#[repr(C)]
+#[derive(Copy, Clone)]
+pub union ZendClassEntryParent {
+    pub parent: *mut ZendClassEntry,
+    pub parent_name: *mut ZendString,
+}
+
+#[repr(C)]
+#[derive(Copy, Clone)]
+pub union ZendClassEntryCreateObject {
+    pub create_object:
+        Option<unsafe extern "C" fn(class_type: *mut ZendClassEntry) -> *mut ZendObject>,
+    pub interface_gets_implemented: Option<
+        unsafe extern "C" fn(iface: *mut ZendClassEntry, class_type: *mut ZendClassEntry) -> c_int,
+    >,
+}
+
+#[repr(C)]
+#[derive(Copy, Clone)]
+pub struct ZendClassEntryInfoUser {
+    pub filename: *mut ZendString,
+    pub line_start: u32,
+    pub line_end: u32,
+    pub doc_comment: *mut ZendString,
+}
+
+#[repr(C)]
+#[derive(Copy, Clone)]
+pub struct ZendClassEntryInfoInternal {
+    pub builtin_functions: *const ZendFunctionEntry,
+    pub module: *mut ZendModuleEntry,
+}
+
+#[repr(C)]
+#[derive(Copy, Clone)]
+pub union ZendClassEntryInfo {
+    pub user: ZendClassEntryInfoUser,
+    pub internal: ZendClassEntryInfoInternal,
+}
+
+#[repr(C)]
+pub struct ZendClassEntry {
+    pub ty: c_char,
+    pub name: *mut ZendString,
+    pub parent: ZendClassEntryParent,
+    pub refcount: c_int,
+    pub ce_flags: u32,
+
+    pub default_properties_count: c_int,
+    pub default_static_members_count: c_int,
+    pub default_properties_table: *mut Zval,
+    pub default_static_members_table: *mut Zval,
+    pub static_members_table_ptr: *mut *mut Zval,
+    pub function_table: HashTable,
+    pub properties_info: HashTable,
+    pub constants_table: HashTable,
+
+    pub mutable_data_ptr: *mut c_void,
+    pub inheritance_cache: *mut c_void,
+
+    pub properties_info_table: *mut *mut c_void,
+
+    pub constructor: *mut ZendFunction,
+    pub destructor: *mut ZendFunction,
+    pub clone: *mut ZendFunction,
+    pub __get: *mut ZendFunction,
+    pub __set: *mut ZendFunction,
+    pub __unset: *mut ZendFunction,
+    pub __isset: *mut ZendFunction,
+    pub __call: *mut ZendFunction,
+    pub __callstatic: *mut ZendFunction,
+    pub __tostring: *mut ZendFunction,
+    pub __debug_info: *mut ZendFunction,
+    pub __serialize: *mut ZendFunction,
+    pub __unserialize: *mut ZendFunction,
+
+    pub iterator_funcs_ptr: *mut c_void,
+    pub arrayaccess_funcs_ptr: *mut c_void,
+
+    pub create_object: ZendClassEntryCreateObject,
+    pub get_iterator: *mut c_void,
+    pub get_static_method: Option<
+        unsafe extern "C" fn(ce: *mut ZendClassEntry, method: *mut ZendString) -> *mut ZendFunction,
+    >,
+
+    pub serialize: *mut c_void,
+    pub unserialize: *mut c_void,
+
+    pub num_interfaces: u32,
+    pub num_traits: u32,
+    pub interfaces: *mut c_void,
+
+    pub trait_names: *mut c_void,
+    pub trait_aliases: *mut c_void,
+    pub trait_precedences: *mut c_void,
+    pub attributes: *mut HashTable,
+
+    pub enum_backing_type: u32,
+    pub backed_enum_table: *mut HashTable,
+
+    pub info: ZendClassEntryInfo,
+}
+
+#[repr(C)]
+#[derive(Debug)]
+pub struct ZendResource {
+    pub gc: ZendRefCountedH,
+    pub handle: ZendLong,
+    pub ty: c_int,
+    pub ptr: *mut c_void,
+}
+
+#[repr(C)]
+#[derive(Debug)]
+pub struct ZendReference {
+    pub gc: ZendRefCountedH,
+    pub val: Zval,
+    pub sources: *mut c_void, // TODO: ZendPropertyInfoSourceList
+}
+
 #[repr(C)]
 #[derive(Debug)]
 pub struct ZendValueWw {
@@ -158,21 +347,21 @@ pub union ZendValue {
     pub counted: *mut ZendRefCounted,
     pub str: *mut ZendString,
     pub arr: *mut ZendArray,
-    // pub obj: *mut ZendObject,
-    // pub res: *mut ZendResource,
-    // pub ref_: *mut ZendReference,
+    pub obj: *mut ZendObject,
+    pub res: *mut ZendResource,
+    pub ref_: *mut ZendReference,
     // pub ast: *mut ZendAstRef,
     pub zv: *mut Zval,
     pub ptr: *mut c_void,
-    // pub ce: *mut ZendClassEntry,
-    // pub func: *mut ZendFunction,
+    pub ce: *mut ZendClassEntry,
+    pub func: *mut ZendFunction,
     pub ww: ManuallyDrop<ZendValueWw>,
 }
 
 impl Debug for ZendValue {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         unsafe {
-            write!(f, "Union(lval: {:?}, dval: {:?}, counted: {:?}, str: {:?}, arr: {:?}, zv: {:?}, ptr: {:?}, ww: {:?})", &self.lval, &self.dval, &self.counted, &self.str, &self.arr, &self.zv, &self.ptr, &self.ww)
+            write!(f, "Union(lval: {:?}, dval: {:?}, counted: {:?}, str: {:?}, arr: {:?}, obj: {:?}, res: {:?}, ref_: {:?}, zv: {:?}, ptr: {:?}, ww: {:?})", &self.lval, &self.dval, &self.counted, &self.str, &self.arr, &self.obj, &self.res, &self.ref_, &self.zv, &self.ptr, &self.ww)
         }
     }
 }
@@ -198,14 +387,39 @@ pub struct Zval {
 
 pub type ZendStat = stat;
 
+pub type ZifHandler =
+    unsafe extern "C" fn(execute_data: *mut ZendExecuteData, return_value: *mut Zval);
+
 #[repr(C)]
 #[derive(Debug)]
 pub struct ZendFunctionEntry {
-    fname: *const c_char,
-    // handler: ZifHandler,
-    // arg_info: *const ZendInternalArgInfo,
-    num_args: u32,
-    flags: u32,
+    pub fname: *const c_char,
+    pub handler: Option<ZifHandler>,
+    pub arg_info: *const ZendInternalArgInfo,
+    pub num_args: u32,
+    pub flags: u32,
+}
+
+pub const _ZEND_TYPE_EXTRA_FLAGS_SHIFT: u32 = 25;
+pub const _ZEND_TYPE_MASK: u32 = (1 << 25) - 1;
+pub const _ZEND_TYPE_NAME_BIT: u32 = 1 << 24;
+pub const _ZEND_TYPE_NULLABLE_BIT: u32 = 0x2;
+pub const _ZEND_SEND_MODE_SHIFT: u32 = _ZEND_TYPE_EXTRA_FLAGS_SHIFT;
+pub const _ZEND_IS_VARIADIC_BIT: u32 = 1 << (_ZEND_TYPE_EXTRA_FLAGS_SHIFT + 2);
+
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct ZendType {
+    pub ptr: *mut c_void,
+    pub type_mask: u32,
+}
+
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct ZendInternalArgInfo {
+    pub name: *const c_char,
+    pub ty: ZendType,
+    pub default_value: *const c_char,
 }
 
 #[repr(C)]
@@ -232,6 +446,8 @@ pub struct ZendLlist {
 
 extern "C" {
     pub fn zend_signal_startup();
+
+    pub fn zval_ptr_dtor(zval_ptr: *mut Zval);
 }
 
 #[cfg(feature = "zts")]
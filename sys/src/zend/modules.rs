@@ -0,0 +1,96 @@
+use std::ffi::{c_char, c_int, c_uchar, c_ushort, c_void};
+use std::mem::size_of;
+
+use crate::zend::{ZendFunctionEntry, ZendResult};
+
+pub const ZEND_MODULE_API_NO: u32 = 20220829;
+
+#[cfg(feature = "zend_debug")]
+pub const ZEND_DEBUG: c_uchar = 1;
+#[cfg(not(feature = "zend_debug"))]
+pub const ZEND_DEBUG: c_uchar = 0;
+
+#[cfg(feature = "zts")]
+pub const USING_ZTS: c_uchar = 1;
+#[cfg(not(feature = "zts"))]
+pub const USING_ZTS: c_uchar = 0;
+
+#[cfg(all(feature = "zts", feature = "zend_debug"))]
+pub const ZEND_MODULE_BUILD_ID: &[u8] = b"API20220829,TS,debug\0";
+#[cfg(all(feature = "zts", not(feature = "zend_debug")))]
+pub const ZEND_MODULE_BUILD_ID: &[u8] = b"API20220829,TS\0";
+#[cfg(all(not(feature = "zts"), feature = "zend_debug"))]
+pub const ZEND_MODULE_BUILD_ID: &[u8] = b"API20220829,NTS,debug\0";
+#[cfg(all(not(feature = "zts"), not(feature = "zend_debug")))]
+pub const ZEND_MODULE_BUILD_ID: &[u8] = b"API20220829,NTS\0";
+
+pub const MODULE_PERSISTENT: c_uchar = 1;
+pub const MODULE_TEMPORARY: c_uchar = 2;
+
+pub type ZendModuleStartupFunc =
+    unsafe extern "C" fn(ty: c_int, module_number: c_int) -> ZendResult;
+pub type ZendModuleShutdownFunc =
+    unsafe extern "C" fn(ty: c_int, module_number: c_int) -> ZendResult;
+pub type ZendModuleInfoFunc = unsafe extern "C" fn(zend_module: *mut ZendModuleEntry);
+
+#[repr(C)]
+#[derive(Debug)]
+pub struct ZendModuleEntry {
+    pub size: c_ushort,
+    pub zend_api: u32,
+    pub zend_debug: c_uchar,
+    pub zts: c_uchar,
+    pub ini_entry: *const c_void, // TODO: ZendIniEntry
+    pub deps: *const c_void,      // TODO: ZendModuleDep
+    pub name: *const c_char,
+    pub functions: *const ZendFunctionEntry,
+    pub module_startup_func: Option<ZendModuleStartupFunc>,
+    pub module_shutdown_func: Option<ZendModuleShutdownFunc>,
+    pub request_startup_func: Option<ZendModuleStartupFunc>,
+    pub request_shutdown_func: Option<ZendModuleShutdownFunc>,
+    pub info_func: Option<ZendModuleInfoFunc>,
+    pub version: *const c_char,
+    pub globals_size: usize,
+    pub globals_ptr: *mut c_void,
+    pub globals_ctor: Option<unsafe extern "C" fn(global: *mut c_void)>,
+    pub globals_dtor: Option<unsafe extern "C" fn(global: *mut c_void)>,
+    pub post_deactivate_func: Option<unsafe extern "C" fn() -> ZendResult>,
+    pub module_started: c_int,
+    pub ty: c_uchar,
+    pub handle: *mut c_void,
+    pub module_number: c_int,
+    pub build_id: *const c_char,
+}
+
+impl ZendModuleEntry {
+    /// An entry filled in like `STANDARD_MODULE_HEADER` and
+    /// `STANDARD_MODULE_PROPERTIES` would, without any hooks.
+    pub fn new(name: *const c_char, version: *const c_char) -> Self {
+        Self {
+            size: size_of::<Self>() as c_ushort,
+            zend_api: ZEND_MODULE_API_NO,
+            zend_debug: ZEND_DEBUG,
+            zts: USING_ZTS,
+            ini_entry: std::ptr::null(),
+            deps: std::ptr::null(),
+            name,
+            functions: std::ptr::null(),
+            module_startup_func: None,
+            module_shutdown_func: None,
+            request_startup_func: None,
+            request_shutdown_func: None,
+            info_func: None,
+            version,
+            globals_size: 0,
+            globals_ptr: std::ptr::null_mut(),
+            globals_ctor: None,
+            globals_dtor: None,
+            post_deactivate_func: None,
+            module_started: 0,
+            ty: 0,
+            handle: std::ptr::null_mut(),
+            module_number: 0,
+            build_id: ZEND_MODULE_BUILD_ID.as_ptr() as *const c_char,
+        }
+    }
+}
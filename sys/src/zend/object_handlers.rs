@@ -0,0 +1,134 @@
+use std::ffi::{c_int, c_void};
+
+use crate::zend::{
+    HashTable, ZendClassEntry, ZendFunction, ZendLong, ZendObject, ZendResult, ZendString,
+    ZendUchar, Zval,
+};
+
+pub const BP_VAR_R: c_int = 0;
+pub const BP_VAR_W: c_int = 1;
+pub const BP_VAR_RW: c_int = 2;
+pub const BP_VAR_IS: c_int = 3;
+pub const BP_VAR_FUNC_ARG: c_int = 4;
+pub const BP_VAR_UNSET: c_int = 5;
+
+pub const ZEND_PROPERTY_ISSET: c_int = 0x0; // Property exists and is not NULL
+pub const ZEND_PROPERTY_NOT_EMPTY: c_int = 0x1; // Property is not empty
+pub const ZEND_PROPERTY_EXISTS: c_int = 0x2; // Property exists
+
+pub type ZendObjectFreeObj = unsafe extern "C" fn(object: *mut ZendObject);
+pub type ZendObjectDtorObj = unsafe extern "C" fn(object: *mut ZendObject);
+pub type ZendObjectCloneObj = unsafe extern "C" fn(old_object: *mut ZendObject) -> *mut ZendObject;
+pub type ZendObjectReadProperty = unsafe extern "C" fn(
+    object: *mut ZendObject,
+    member: *mut ZendString,
+    ty: c_int,
+    cache_slot: *mut *mut c_void,
+    rv: *mut Zval,
+) -> *mut Zval;
+pub type ZendObjectWriteProperty = unsafe extern "C" fn(
+    object: *mut ZendObject,
+    member: *mut ZendString,
+    value: *mut Zval,
+    cache_slot: *mut *mut c_void,
+) -> *mut Zval;
+pub type ZendObjectReadDimension = unsafe extern "C" fn(
+    object: *mut ZendObject,
+    offset: *mut Zval,
+    ty: c_int,
+    rv: *mut Zval,
+) -> *mut Zval;
+pub type ZendObjectWriteDimension =
+    unsafe extern "C" fn(object: *mut ZendObject, offset: *mut Zval, value: *mut Zval);
+pub type ZendObjectGetPropertyPtrPtr = unsafe extern "C" fn(
+    object: *mut ZendObject,
+    member: *mut ZendString,
+    ty: c_int,
+    cache_slot: *mut *mut c_void,
+) -> *mut Zval;
+pub type ZendObjectHasProperty = unsafe extern "C" fn(
+    object: *mut ZendObject,
+    member: *mut ZendString,
+    has_set_exists: c_int,
+    cache_slot: *mut *mut c_void,
+) -> c_int;
+pub type ZendObjectUnsetProperty = unsafe extern "C" fn(
+    object: *mut ZendObject,
+    member: *mut ZendString,
+    cache_slot: *mut *mut c_void,
+);
+pub type ZendObjectHasDimension =
+    unsafe extern "C" fn(object: *mut ZendObject, member: *mut Zval, check_empty: c_int) -> c_int;
+pub type ZendObjectUnsetDimension =
+    unsafe extern "C" fn(object: *mut ZendObject, offset: *mut Zval);
+pub type ZendObjectGetProperties = unsafe extern "C" fn(object: *mut ZendObject) -> *mut HashTable;
+pub type ZendObjectGetMethod = unsafe extern "C" fn(
+    object: *mut *mut ZendObject,
+    method: *mut ZendString,
+    key: *const Zval,
+) -> *mut ZendFunction;
+pub type ZendObjectGetConstructor =
+    unsafe extern "C" fn(object: *mut ZendObject) -> *mut ZendFunction;
+pub type ZendObjectGetClassName =
+    unsafe extern "C" fn(object: *const ZendObject) -> *mut ZendString;
+pub type ZendObjectCast =
+    unsafe extern "C" fn(readobj: *mut ZendObject, retval: *mut Zval, ty: c_int) -> ZendResult;
+pub type ZendObjectCountElements =
+    unsafe extern "C" fn(object: *mut ZendObject, count: *mut ZendLong) -> ZendResult;
+pub type ZendObjectGetDebugInfo =
+    unsafe extern "C" fn(object: *mut ZendObject, is_temp: *mut c_int) -> *mut HashTable;
+pub type ZendObjectGetClosure = unsafe extern "C" fn(
+    obj: *mut ZendObject,
+    ce_ptr: *mut *mut ZendClassEntry,
+    fptr_ptr: *mut *mut ZendFunction,
+    obj_ptr: *mut *mut ZendObject,
+    check_only: bool,
+) -> ZendResult;
+pub type ZendObjectGetGc = unsafe extern "C" fn(
+    object: *mut ZendObject,
+    table: *mut *mut Zval,
+    n: *mut c_int,
+) -> *mut HashTable;
+pub type ZendObjectDoOperation = unsafe extern "C" fn(
+    opcode: ZendUchar,
+    result: *mut Zval,
+    op1: *mut Zval,
+    op2: *mut Zval,
+) -> ZendResult;
+pub type ZendObjectCompare = unsafe extern "C" fn(object1: *mut Zval, object2: *mut Zval) -> c_int;
+pub type ZendObjectGetPropertiesFor =
+    unsafe extern "C" fn(object: *mut ZendObject, purpose: c_int) -> *mut HashTable;
+
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct ZendObjectHandlers {
+    pub offset: c_int,
+    pub free_obj: Option<ZendObjectFreeObj>,
+    pub dtor_obj: Option<ZendObjectDtorObj>,
+    pub clone_obj: Option<ZendObjectCloneObj>,
+    pub read_property: Option<ZendObjectReadProperty>,
+    pub write_property: Option<ZendObjectWriteProperty>,
+    pub read_dimension: Option<ZendObjectReadDimension>,
+    pub write_dimension: Option<ZendObjectWriteDimension>,
+    pub get_property_ptr_ptr: Option<ZendObjectGetPropertyPtrPtr>,
+    pub has_property: Option<ZendObjectHasProperty>,
+    pub unset_property: Option<ZendObjectUnsetProperty>,
+    pub has_dimension: Option<ZendObjectHasDimension>,
+    pub unset_dimension: Option<ZendObjectUnsetDimension>,
+    pub get_properties: Option<ZendObjectGetProperties>,
+    pub get_method: Option<ZendObjectGetMethod>,
+    pub get_constructor: Option<ZendObjectGetConstructor>,
+    pub get_class_name: Option<ZendObjectGetClassName>,
+    pub cast_object: Option<ZendObjectCast>,
+    pub count_elements: Option<ZendObjectCountElements>,
+    pub get_debug_info: Option<ZendObjectGetDebugInfo>,
+    pub get_closure: Option<ZendObjectGetClosure>,
+    pub get_gc: Option<ZendObjectGetGc>,
+    pub do_operation: Option<ZendObjectDoOperation>,
+    pub compare: Option<ZendObjectCompare>,
+    pub get_properties_for: Option<ZendObjectGetPropertiesFor>,
+}
+
+extern "C" {
+    pub static std_object_handlers: ZendObjectHandlers;
+}
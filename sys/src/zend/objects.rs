@@ -0,0 +1,22 @@
+use std::mem::size_of;
+
+use crate::zend::compile::ZEND_ACC_USE_GUARDS;
+use crate::zend::{ZendClassEntry, ZendObject, Zval};
+
+extern "C" {
+    pub fn zend_object_std_init(object: *mut ZendObject, ce: *mut ZendClassEntry);
+    pub fn zend_object_std_dtor(object: *mut ZendObject);
+    pub fn zend_objects_destroy_object(object: *mut ZendObject);
+}
+
+/// The size of the property slots trailing a `zend_object`, beyond the one
+/// already counted in `size_of::<ZendObject>()`.
+#[inline]
+pub unsafe fn zend_object_properties_size(ce: *const ZendClassEntry) -> usize {
+    let count = (*ce).default_properties_count as usize;
+    let used = match (*ce).ce_flags & ZEND_ACC_USE_GUARDS != 0 {
+        true => count,
+        _ => count.wrapping_sub(1),
+    };
+    size_of::<Zval>().wrapping_mul(used)
+}
@@ -0,0 +1,34 @@
+use std::ffi::c_int;
+
+use crate::zend::compile::ZendExecuteData;
+use crate::zend::{ZendString, Zval};
+
+pub type ZendObserverErrorCb = unsafe extern "C" fn(
+    ty: c_int,
+    error_filename: *mut ZendString,
+    error_lineno: u32,
+    message: *mut ZendString,
+);
+
+pub type ZendObserverFcallBeginHandler = unsafe extern "C" fn(execute_data: *mut ZendExecuteData);
+pub type ZendObserverFcallEndHandler =
+    unsafe extern "C" fn(execute_data: *mut ZendExecuteData, retval: *mut Zval);
+
+#[repr(C)]
+#[derive(Debug, Default)]
+pub struct ZendObserverFcallHandlers {
+    pub begin: Option<ZendObserverFcallBeginHandler>,
+    pub end: Option<ZendObserverFcallEndHandler>,
+}
+
+/// Called on the first call of each function, with the handlers to observe
+/// its calls with, if any.
+pub type ZendObserverFcallInit =
+    unsafe extern "C" fn(execute_data: *mut ZendExecuteData) -> ZendObserverFcallHandlers;
+
+extern "C" {
+    /// Must be called after `zend_observer_startup`, such as from `MINIT`.
+    pub fn zend_observer_error_register(callback: ZendObserverErrorCb);
+    /// Must be called from `MINIT`, before any function is compiled.
+    pub fn zend_observer_fcall_register(init: ZendObserverFcallInit);
+}
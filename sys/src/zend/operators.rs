@@ -0,0 +1,16 @@
+use crate::zend::ZendClassEntry;
+
+extern "C" {
+    pub fn instanceof_function_slow(
+        instance_ce: *const ZendClassEntry,
+        ce: *const ZendClassEntry,
+    ) -> bool;
+}
+
+#[inline]
+pub unsafe fn instanceof_function(
+    instance_ce: *const ZendClassEntry,
+    ce: *const ZendClassEntry,
+) -> bool {
+    instance_ce == ce || instanceof_function_slow(instance_ce, ce)
+}
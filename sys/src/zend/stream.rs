@@ -49,4 +49,5 @@ pub struct ZendFileHandle {
 extern "C" {
     pub fn zend_stream_init_fp(handle: *mut ZendFileHandle, fp: RawFd, filename: *const c_char);
     pub fn zend_stream_init_filename(handle: *mut ZendFileHandle, filename: *const c_char);
+    pub fn zend_destroy_file_handle(handle: *mut ZendFileHandle);
 }
@@ -1,9 +1,30 @@
-use crate::zend::ZendString;
+use std::ffi::c_char;
+use std::mem::offset_of;
+use std::ptr::copy_nonoverlapping;
+
+use crate::zend::alloc::{pefree, pemalloc, zend_mm_aligned_size};
+use crate::zend::{
+    ZendRefCountedH, ZendRefCountedHTypeInfo, ZendString, ZendUlong, GC_FLAGS_SHIFT, GC_PERSISTENT,
+    GC_STRING,
+};
 
 pub const ZEND_STR_AUTOGLOBAL_SERVER: usize = 66;
 
+pub const IS_STR_INTERNED: u32 = crate::zend::GC_IMMUTABLE; // interned string
+pub const IS_STR_PERSISTENT: u32 = GC_PERSISTENT; // allocated using malloc
+pub const IS_STR_PERMANENT: u32 = 1 << 8; // relives request boundary
+pub const IS_STR_VALID_UTF8: u32 = 1 << 9; // valid UTF-8 according to PCRE
+
 extern "C" {
     pub static zend_known_strings: *mut *mut ZendString;
+
+    pub static zend_new_interned_string:
+        unsafe extern "C" fn(str: *mut ZendString) -> *mut ZendString;
+    pub static zend_string_init_interned:
+        unsafe extern "C" fn(str: *const c_char, size: usize, permanent: bool) -> *mut ZendString;
+
+    pub fn zend_string_hash_func(s: *mut ZendString) -> ZendUlong;
+    pub fn zend_hash_func(str: *const c_char, len: usize) -> ZendUlong;
 }
 
 #[macro_export]
@@ -14,3 +35,82 @@ macro_rules! zstr_known {
 }
 
 pub use zstr_known;
+
+pub const ZSTR_HEADER_SIZE: usize = offset_of!(ZendString, val);
+
+#[inline]
+pub const fn zstr_struct_size(len: usize) -> usize {
+    ZSTR_HEADER_SIZE + len + 1
+}
+
+#[inline]
+pub unsafe fn zstr_val(s: *mut ZendString) -> *mut c_char {
+    (*s).val.as_mut_ptr()
+}
+
+#[inline]
+pub unsafe fn zend_string_alloc(len: usize, persistent: bool) -> *mut ZendString {
+    let ret = pemalloc(zend_mm_aligned_size(zstr_struct_size(len)), persistent) as *mut ZendString;
+
+    (*ret).gc = ZendRefCountedH {
+        ref_count: 1,
+        u: ZendRefCountedHTypeInfo {
+            type_info: GC_STRING
+                | (match persistent {
+                    true => IS_STR_PERSISTENT,
+                    _ => 0,
+                } << GC_FLAGS_SHIFT),
+        },
+    };
+    (*ret).h = 0;
+    (*ret).len = len;
+    ret
+}
+
+#[inline]
+pub unsafe fn zend_string_init(
+    str: *const c_char,
+    len: usize,
+    persistent: bool,
+) -> *mut ZendString {
+    let ret = zend_string_alloc(len, persistent);
+
+    copy_nonoverlapping(str, zstr_val(ret), len);
+    *zstr_val(ret).add(len) = 0;
+    ret
+}
+
+#[inline]
+pub unsafe fn zstr_is_interned(s: *const ZendString) -> bool {
+    (*s).gc.u.type_info & (IS_STR_INTERNED << GC_FLAGS_SHIFT) != 0
+}
+
+#[inline]
+pub unsafe fn zend_string_hash_val(s: *mut ZendString) -> ZendUlong {
+    match (*s).h {
+        0 => zend_string_hash_func(s),
+        h => h,
+    }
+}
+
+#[inline]
+pub unsafe fn zend_string_copy(s: *mut ZendString) -> *mut ZendString {
+    if !zstr_is_interned(s) {
+        (*s).gc.ref_count += 1;
+    }
+    s
+}
+
+#[inline]
+pub unsafe fn zend_string_release(s: *mut ZendString) {
+    if !zstr_is_interned(s) {
+        (*s).gc.ref_count -= 1;
+
+        if (*s).gc.ref_count == 0 {
+            pefree(
+                s as *mut _,
+                (*s).gc.u.type_info & (IS_STR_PERSISTENT << GC_FLAGS_SHIFT) != 0,
+            );
+        }
+    }
+}
@@ -0,0 +1,32 @@
+use crate::zend::{
+    IS_ARRAY, IS_CALLABLE, IS_DOUBLE, IS_FALSE, IS_ITERABLE, IS_LONG, IS_NEVER, IS_NULL, IS_OBJECT,
+    IS_REFERENCE, IS_RESOURCE, IS_STATIC, IS_STRING, IS_TRUE, IS_UNDEF, IS_VOID,
+};
+
+pub const MAY_BE_UNDEF: u32 = 1 << IS_UNDEF;
+pub const MAY_BE_NULL: u32 = 1 << IS_NULL;
+pub const MAY_BE_FALSE: u32 = 1 << IS_FALSE;
+pub const MAY_BE_TRUE: u32 = 1 << IS_TRUE;
+pub const MAY_BE_BOOL: u32 = MAY_BE_FALSE | MAY_BE_TRUE;
+pub const MAY_BE_LONG: u32 = 1 << IS_LONG;
+pub const MAY_BE_DOUBLE: u32 = 1 << IS_DOUBLE;
+pub const MAY_BE_STRING: u32 = 1 << IS_STRING;
+pub const MAY_BE_ARRAY: u32 = 1 << IS_ARRAY;
+pub const MAY_BE_OBJECT: u32 = 1 << IS_OBJECT;
+pub const MAY_BE_RESOURCE: u32 = 1 << IS_RESOURCE;
+pub const MAY_BE_ANY: u32 = MAY_BE_NULL
+    | MAY_BE_FALSE
+    | MAY_BE_TRUE
+    | MAY_BE_LONG
+    | MAY_BE_DOUBLE
+    | MAY_BE_STRING
+    | MAY_BE_ARRAY
+    | MAY_BE_OBJECT
+    | MAY_BE_RESOURCE;
+pub const MAY_BE_REF: u32 = 1 << IS_REFERENCE;
+
+pub const MAY_BE_CALLABLE: u32 = 1 << IS_CALLABLE;
+pub const MAY_BE_ITERABLE: u32 = 1 << IS_ITERABLE;
+pub const MAY_BE_VOID: u32 = 1 << IS_VOID;
+pub const MAY_BE_NEVER: u32 = 1 << IS_NEVER;
+pub const MAY_BE_STATIC: u32 = 1 << IS_STATIC;